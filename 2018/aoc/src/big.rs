@@ -0,0 +1,305 @@
+//! Signed integers of any size, for puzzles whose numbers outgrow `i128`.
+//!
+//! Only what the solutions need is there: parsing, printing, comparing,
+//! adding, subtracting and Euclidean division.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Neg, Sub};
+use std::str::FromStr;
+
+/// Integer of any size.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Big {
+    negative: bool,
+    /// Base 2^32 digits, lowest first and without trailing zeros, so zero is
+    /// empty (and never negative).
+    digits: Vec<u32>
+}
+
+// NOTE: Helpers below work on magnitudes, trimmed of trailing zeros
+
+fn trim( mut digits: Vec<u32> ) -> Vec<u32> {
+    while digits.last() == Some( &0 ) {
+        digits.pop();
+    }
+
+    digits
+}
+
+fn cmp_magnitude( a: &[u32], b: &[u32] ) -> Ordering {
+    a.len().cmp( &b.len() ).then_with( || a.iter().rev().cmp( b.iter().rev() ) )
+}
+
+fn add_magnitude( a: &[u32], b: &[u32] ) -> Vec<u32> {
+    let mut sum = Vec::with_capacity( a.len().max( b.len() ) + 1 );
+    let mut carry = 0u64;
+
+    for i in 0 .. a.len().max( b.len() ) {
+        let digit = *a.get( i ).unwrap_or( &0 ) as u64 + *b.get( i ).unwrap_or( &0 ) as u64 + carry;
+        sum.push( digit as u32 );
+        carry = digit >> 32;
+    }
+
+    sum.push( carry as u32 );
+    trim( sum )
+}
+
+// NOTE: `a` can't be smaller than `b`
+fn sub_magnitude( a: &[u32], b: &[u32] ) -> Vec<u32> {
+    let mut difference = Vec::with_capacity( a.len() );
+    let mut borrow = 0i64;
+
+    for (i, &digit) in a.iter().enumerate() {
+        let mut digit = digit as i64 - *b.get( i ).unwrap_or( &0 ) as i64 - borrow;
+        borrow = (digit < 0) as i64;
+        digit += borrow << 32;
+        difference.push( digit as u32 );
+    }
+
+    trim( difference )
+}
+
+fn mul_add_small( a: &mut Vec<u32>, factor: u32, addend: u32 ) {
+    let mut carry = addend as u64;
+
+    for digit in a.iter_mut() {
+        let product = *digit as u64 * factor as u64 + carry;
+        *digit = product as u32;
+        carry = product >> 32;
+    }
+
+    if carry > 0 {
+        a.push( carry as u32 );
+    }
+}
+
+fn div_small( a: &mut Vec<u32>, divisor: u32 ) -> u32 {
+    let mut remainder = 0u64;
+
+    for digit in a.iter_mut().rev() {
+        let current = (remainder << 32) | *digit as u64;
+        *digit = (current / divisor as u64) as u32;
+        remainder = current % divisor as u64;
+    }
+
+    *a = trim( std::mem::take( a ) );
+    remainder as u32
+}
+
+// NOTE: Plain long division, one bit at a time
+fn div_rem_magnitude( a: &[u32], b: &[u32] ) -> (Vec<u32>, Vec<u32>) {
+    let mut quotient = vec![ 0; a.len() ];
+    let mut remainder = vec![];
+
+    for bit in ( 0 .. a.len() * 32 ).rev() {
+        mul_add_small( &mut remainder, 2, (a[ bit / 32 ] >> (bit % 32)) & 1 );
+
+        if cmp_magnitude( &remainder, b ) != Ordering::Less {
+            remainder = sub_magnitude( &remainder, b );
+            quotient[ bit / 32 ] |= 1 << (bit % 32);
+        }
+    }
+
+    (trim( quotient ), remainder)
+}
+
+impl Big {
+    fn new( negative: bool, digits: Vec<u32> ) -> Self {
+        let digits = trim( digits );
+        Big { negative: negative && ! digits.is_empty(), digits }
+    }
+
+    pub fn is_negative( &self ) -> bool {
+        self.negative
+    }
+
+    pub fn is_zero( &self ) -> bool {
+        self.digits.is_empty()
+    }
+
+    /// Value as a `u128`, if it fits.
+    pub fn to_u128( &self ) -> Option<u128> {
+        if self.negative || self.digits.len() > 4 {
+            return None;
+        }
+
+        Some( self.digits.iter().rev().fold( 0, |n, &digit| (n << 32) | digit as u128 ) )
+    }
+
+    /// Quotient and remainder of dividing by `divisor`, the remainder never
+    /// being negative. `None` for a zero divisor.
+    pub fn div_rem_euclid( &self, divisor: &Big ) -> Option<(Big, Big)> {
+        if divisor.is_zero() {
+            return None;
+        }
+
+        let (quotient, remainder) = div_rem_magnitude( &self.digits, &divisor.digits );
+        let quotient = Big::new( self.negative != divisor.negative, quotient );
+        let remainder = Big::new( self.negative, remainder );
+
+        if ! remainder.is_negative() {
+            return Some( (quotient, remainder) );
+        }
+
+        let one = Big::from( 1 );
+        let quotient = if divisor.negative { quotient + one } else { quotient - one };
+        let remainder = remainder + Big::new( false, divisor.digits.clone() );
+
+        Some( (quotient, remainder) )
+    }
+}
+
+impl From<i128> for Big {
+    fn from( n: i128 ) -> Self {
+        let magnitude = n.unsigned_abs();
+        let digits = (0 .. 4).map( |i| (magnitude >> (32 * i)) as u32 ).collect();
+
+        Big::new( n < 0, digits )
+    }
+}
+
+impl Ord for Big {
+    fn cmp( &self, other: &Self ) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true)   => Ordering::Greater,
+            (true, false)   => Ordering::Less,
+            (false, false)  => cmp_magnitude( &self.digits, &other.digits ),
+            (true, true)    => cmp_magnitude( &other.digits, &self.digits )
+        }
+    }
+}
+
+impl PartialOrd for Big {
+    fn partial_cmp( &self, other: &Self ) -> Option<Ordering> {
+        Some( self.cmp( other ) )
+    }
+}
+
+impl Neg for Big {
+    type Output = Big;
+
+    fn neg( self ) -> Big {
+        Big::new( ! self.negative, self.digits )
+    }
+}
+
+impl Add for Big {
+    type Output = Big;
+
+    fn add( self, other: Big ) -> Big {
+        if self.negative == other.negative {
+            return Big::new( self.negative, add_magnitude( &self.digits, &other.digits ) );
+        }
+
+        match cmp_magnitude( &self.digits, &other.digits ) {
+            Ordering::Less  => Big::new( other.negative, sub_magnitude( &other.digits, &self.digits ) ),
+            _               => Big::new( self.negative, sub_magnitude( &self.digits, &other.digits ) )
+        }
+    }
+}
+
+impl Sub for Big {
+    type Output = Big;
+
+    fn sub( self, other: Big ) -> Big {
+        self + -other
+    }
+}
+
+/// Why a string isn't an integer.
+#[derive(Debug, PartialEq)]
+pub struct ParseBigError;
+
+impl fmt::Display for ParseBigError {
+    fn fmt( &self, f: &mut fmt::Formatter ) -> fmt::Result {
+        write!( f, "invalid integer" )
+    }
+}
+
+impl std::error::Error for ParseBigError {}
+
+/// Decimal integer with an optional sign, e.g. `+1` or `-7`.
+impl FromStr for Big {
+    type Err = ParseBigError;
+
+    fn from_str( s: &str ) -> Result<Self, Self::Err> {
+        let (negative, number) = match s.as_bytes().first() {
+            Some( b'-' )    => (true, &s[ 1 .. ]),
+            Some( b'+' )    => (false, &s[ 1 .. ]),
+            _               => (false, s)
+        };
+
+        if number.is_empty() || ! number.bytes().all( |b| b.is_ascii_digit() ) {
+            return Err( ParseBigError );
+        }
+
+        let mut digits = vec![];
+        for b in number.bytes() {
+            mul_add_small( &mut digits, 10, (b - b'0') as u32 );
+        }
+
+        Ok( Big::new( negative, digits ) )
+    }
+}
+
+impl fmt::Display for Big {
+    fn fmt( &self, f: &mut fmt::Formatter ) -> fmt::Result {
+        let mut digits = self.digits.clone();
+        let mut chunks = vec![];
+
+        while ! digits.is_empty() {
+            chunks.push( div_small( &mut digits, 1_000_000_000 ) );
+        }
+
+        let mut text = chunks.pop().unwrap_or( 0 ).to_string();
+        for chunk in chunks.iter().rev() {
+            text.push_str( &format!( "{:09}", chunk ) );
+        }
+
+        f.pad_integral( ! self.negative, "", &text )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn agrees_with_i128( a in any::<i64>(), b in any::<i64>() ) {
+            let (a, b) = (a as i128 * 3, b as i128);
+            let (big_a, big_b) = (Big::from( a ), Big::from( b ));
+
+            prop_assert_eq!( big_a.to_string(), a.to_string() );
+            prop_assert_eq!( a.to_string().parse::<Big>(), Ok( big_a.clone() ) );
+            prop_assert_eq!( (big_a.clone() + big_b.clone()).to_string(), (a + b).to_string() );
+            prop_assert_eq!( (big_a.clone() - big_b.clone()).to_string(), (a - b).to_string() );
+            prop_assert_eq!( big_a.cmp( &big_b ), a.cmp( &b ) );
+
+            if b != 0 {
+                let (quotient, remainder) = big_a.div_rem_euclid( &big_b ).unwrap();
+                prop_assert_eq!( quotient.to_string(), a.div_euclid( b ).to_string() );
+                prop_assert_eq!( remainder.to_string(), a.rem_euclid( b ).to_string() );
+            }
+        }
+    }
+
+    #[test]
+    fn goes_past_i128() {
+        let max = Big::from( i128::MAX );
+        let past = max.clone() + max.clone() + Big::from( 2 );
+
+        assert_eq!( past.to_string(), "340282366920938463463374607431768211456" );
+        assert_eq!( past.div_rem_euclid( &max ), Some( (Big::from( 2 ), Big::from( 2 )) ) );
+        assert_eq!( "+0".parse::<Big>(), Ok( Big::default() ) );
+        assert_eq!( "-0".parse::<Big>().unwrap().to_string(), "0" );
+        assert_eq!( format!( "{:+}", Big::from( 7 ) ), "+7" );
+        assert_eq!( Big::from( i128::MAX ).to_u128(), Some( i128::MAX as u128 ) );
+        assert_eq!( past.to_u128(), None );
+        assert_eq!( Big::from( -1 ).to_u128(), None );
+        assert!( "1-2".parse::<Big>().is_err() );
+        assert!( "-".parse::<Big>().is_err() );
+    }
+}
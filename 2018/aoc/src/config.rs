@@ -0,0 +1,243 @@
+//! Project settings from `aoc.toml`.
+//!
+//! The file is looked up from a day's directory upwards, so the one at the
+//! root of the checkout covers every year. `AOC_CONFIG` may name more files,
+//! separated like in `PATH`, whose settings win over the project ones and
+//! the ones before them. Whatever none of them sets falls back to the
+//! defaults documented in the project file.
+
+use std::env;
+use std::error::Error;
+use std::fmt::Display;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::thread;
+use std::time::Duration;
+
+use serde::Deserialize;
+use toml::{Table, Value};
+
+/// How answers are printed.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    /// Just the answer.
+    Plain,
+    /// One JSON object per answer.
+    Json
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct Defaults {
+    part: String,
+    input: String,
+    format: Format
+}
+
+impl Default for Defaults {
+    fn default() -> Self {
+        Defaults {
+            part: "part1".into(),
+            input: "res/inputs/main.txt".into(),
+            format: Format::Plain
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct Run {
+    timeout: u64
+}
+
+/// Settings as seen by a single day.
+#[derive(Debug)]
+pub struct Config {
+    dir: PathBuf,
+    year: String,
+    day: String,
+    defaults: Defaults,
+    run: Run,
+    params: Table
+}
+
+// NOTE: Tables are merged key by key, anything else is simply replaced
+fn merge( base: &mut Table, over: Table ) {
+    for (key, value) in over {
+        match (base.get_mut( &key ), value) {
+            (Some( Value::Table( base ) ), Value::Table( over ))  => merge( base, over ),
+            (_, value)                                          => { base.insert( key, value ); }
+        }
+    }
+}
+
+fn section<T: Default + for<'de> Deserialize<'de>>( root: &Table, name: &str ) -> Result<T, String> {
+    match root.get( name ) {
+        Some( value )   => value.clone().try_into().map_err( |e| format!( "[{}]: {}", name, e ) ),
+        None            => Ok( T::default() )
+    }
+}
+
+fn read( path: &Path ) -> Result<Table, String> {
+    let text = fs::read_to_string( path ).map_err( |e| format!( "{}: {}", path.display(), e ) )?;
+    text.parse().map_err( |e| format!( "{}: {}", path.display(), e ) )
+}
+
+fn json_string( s: &str ) -> String {
+    let mut json = String::from( "\"" );
+
+    for c in s.chars() {
+        match c {
+            '"'                     => json.push_str( "\\\"" ),
+            '\\'                    => json.push_str( "\\\\" ),
+            c if (c as u32) < 0x20  => json.push_str( &format!( "\\u{:04x}", c as u32 ) ),
+            c                       => json.push( c )
+        }
+    }
+
+    json.push( '"' );
+    json
+}
+
+impl Config {
+    /// Settings for the day living in `dir`, e.g. `env!( "CARGO_MANIFEST_DIR" )`.
+    pub fn load<P: AsRef<Path>>( dir: P ) -> Result<Self, Box<dyn Error>> {
+        let dir = dir.as_ref();
+        let mut sources = vec![];
+
+        if let Some( project ) = dir.ancestors().map( |d| d.join( "aoc.toml" ) ).find( |p| p.is_file() ) {
+            sources.push( read( &project )? );
+        }
+
+        if let Some( user ) = env::var_os( "AOC_CONFIG" ) {
+            for path in env::split_paths( &user ).filter( |p| ! p.as_os_str().is_empty() ) {
+                sources.push( read( &path )? );
+            }
+        }
+
+        Ok( Config::from_tables( dir, sources )? )
+    }
+
+    fn from_tables( dir: &Path, sources: Vec<Table> ) -> Result<Self, String> {
+        let mut root = Table::new();
+        for source in sources {
+            merge( &mut root, source );
+        }
+
+        let name = |p: Option<&Path>| {
+            p.and_then( |p| p.file_name() ).map( |n| n.to_string_lossy().into_owned() ).unwrap_or_default()
+        };
+
+        let (year, day) = (name( dir.parent() ), name( Some( dir ) ));
+        let params = match root.get( &year ).and_then( |y| y.get( &day ) ) {
+            Some( Value::Table( params ) )  => params.clone(),
+            Some( _ )                       => return Err( format!( "[{}.{}] should be a table", year, day ) ),
+            None                            => Table::new()
+        };
+
+        Ok( Config {
+            dir: dir.to_path_buf(),
+            defaults: section( &root, "defaults" )?,
+            run: section( &root, "run" )?,
+            year,
+            day,
+            params
+        } )
+    }
+
+    /// Command to run when none is given.
+    pub fn part( &self ) -> String {
+        self.defaults.part.clone()
+    }
+
+    /// Path of the puzzle input, with `{year}` and `{day}` filled in and
+    /// relative paths starting at the day's directory.
+    pub fn input( &self ) -> String {
+        let number = self.day.trim_start_matches( "day" );
+        let path = self.defaults.input.replace( "{year}", &self.year ).replace( "{day}", number );
+
+        self.dir.join( path ).to_string_lossy().into_owned()
+    }
+
+    pub fn format( &self ) -> Format {
+        self.defaults.format
+    }
+
+    /// How long a single run may take, if there's a limit at all.
+    pub fn timeout( &self ) -> Option<Duration> {
+        Some( Duration::from_secs( self.run.timeout ) ).filter( |t| ! t.is_zero() )
+    }
+
+    /// Ends the process once it runs past the timeout.
+    pub fn watch( &self ) {
+        if let Some( timeout ) = self.timeout() {
+            thread::spawn( move || {
+                thread::sleep( timeout );
+                eprintln!( "timed out after {}s", timeout.as_secs() );
+                process::exit( 124 );
+            } );
+        }
+    }
+
+    /// Puzzle parameter `name` of this day, from its `[<year>.<day>]` table.
+    pub fn param<T: for<'de> Deserialize<'de>>( &self, name: &str, default: T ) -> Result<T, String> {
+        match self.params.get( name ) {
+            Some( value )   => value.clone().try_into().map_err( |e| format!( "{}.{}.{}: {}", self.year, self.day, name, e ) ),
+            None            => Ok( default )
+        }
+    }
+
+    /// Prints the answer to `part` in the configured format.
+    pub fn print<D: Display>( &self, part: &str, answer: D ) {
+        match self.defaults.format {
+            Format::Plain   => println!( "{}", answer ),
+            Format::Json    => println!(
+                "{{\"day\": {}, \"part\": {}, \"answer\": {}}}",
+                json_string( &format!( "{}/{}", self.year, self.day ) ),
+                json_string( part ),
+                json_string( &answer.to_string() )
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config( sources: &[&str] ) -> Result<Config, String> {
+        let tables = sources.iter().map( |s| s.parse::<Table>().unwrap() ).collect();
+        Config::from_tables( Path::new( "/aoc/2018/day6" ), tables )
+    }
+
+    #[test]
+    fn user_settings_win_over_project_ones() {
+        let project = "[defaults]\ninput = \"../inputs/{year}/{day}.txt\"\n[2018.day6]\nthreshold = 10000\nunused = 1";
+        let user = "[defaults]\nformat = \"json\"\n[2018.day6]\nthreshold = 32";
+        let config = config( &[ project, user ] ).unwrap();
+
+        assert_eq!( config.part(), "part1" );
+        assert_eq!( config.input(), "/aoc/2018/day6/../inputs/2018/6.txt" );
+        assert_eq!( config.format(), Format::Json );
+        assert_eq!( config.timeout(), None );
+        assert_eq!( config.param( "threshold", 0 ), Ok( 32 ) );
+        assert_eq!( config.param( "unused", 0 ), Ok( 1 ) );
+        assert_eq!( config.param( "missing", 7 ), Ok( 7 ) );
+    }
+
+    #[test]
+    fn rejects_bad_settings() {
+        assert!( config( &[ "[defaults]\nformat = \"yaml\"" ] ).is_err() );
+        assert!( config( &[ "[run]\ntimeout = -1" ] ).is_err() );
+        assert!( config( &[ "[run]\ntimeuot = 1" ] ).is_err() );
+        assert!( config( &[ "[2018]\nday6 = 1" ] ).is_err() );
+        assert!( config( &[ "[2018.day6]\nthreshold = \"many\"" ] ).unwrap().param( "threshold", 0 ).is_err() );
+    }
+
+    #[test]
+    fn escapes_json_strings() {
+        assert_eq!( json_string( "a \"b\" \\ c\n" ), "\"a \\\"b\\\" \\\\ c\\u000a\"" );
+    }
+}
@@ -0,0 +1,283 @@
+//! Differential testing of optimised solutions against slow references.
+//!
+//! A day lists its checks as pairs of solvers, one optimised and one written
+//! to be obviously correct. Both are run on generated inputs and the first
+//! input they disagree on is minimised, first by asking the generator for a
+//! smaller input with the same seed and then by dropping lines and characters.
+
+use std::env;
+use std::error::Error;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::gen::Rng;
+
+/// Solves a whole input, returning the answer as printed.
+pub type Solver = fn( &[String] ) -> String;
+
+/// Name of the check, the optimised solver and the reference one.
+pub type Check = (&'static str, Solver, Solver);
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Outcome {
+    Answer( String ),
+    Panic( String ),
+    Timeout
+}
+
+impl std::fmt::Display for Outcome {
+    fn fmt( &self, f: &mut std::fmt::Formatter ) -> std::fmt::Result {
+        match self {
+            Outcome::Answer( answer ) => write!( f, "{}", answer ),
+            Outcome::Panic( message ) => write!( f, "panic: {}", message ),
+            Outcome::Timeout          => write!( f, "timed out" )
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Mismatch {
+    pub check: &'static str,
+    pub case: usize,
+    pub size: usize,
+    pub seed: u64,
+    pub input: Vec<String>,
+    pub optimised: Outcome,
+    pub reference: Outcome
+}
+
+fn solve( solver: Solver, input: &[String] ) -> Outcome {
+    match panic::catch_unwind( AssertUnwindSafe( || solver( input ) ) ) {
+        Ok( answer ) => Outcome::Answer( answer ),
+        Err( err )   => {
+            let message = err.downcast_ref::<&str>().map( |s| s.to_string() )
+                            .or_else( || err.downcast_ref::<String>().cloned() )
+                            .unwrap_or_default();
+
+            Outcome::Panic( message )
+        }
+    }
+}
+
+// NOTE: Dropping lines can turn an input into one that never finishes (e.g.
+// frequencies that never repeat), so candidates run on a separate thread which
+// is simply abandoned once it takes too long.
+fn solve_within( solver: Solver, input: &[String], timeout: Duration ) -> Outcome {
+    let (tx, rx) = mpsc::channel();
+    let input = input.to_vec();
+
+    thread::spawn( move || {
+        let _ = tx.send( solve( solver, &input ) );
+    } );
+
+    rx.recv_timeout( timeout ).unwrap_or( Outcome::Timeout )
+}
+
+// An input only counts when the reference can solve it, anything else is
+// most likely not a valid input anymore.
+fn disagree( check: &Check, input: &[String], timeout: Duration ) -> Option<(Outcome, Outcome)> {
+    let reference = solve_within( check.2, input, timeout );
+    if let Outcome::Answer( _ ) = reference {
+        let optimised = solve_within( check.1, input, timeout );
+
+        if optimised != reference {
+            return Some( (optimised, reference) );
+        }
+    }
+
+    None
+}
+
+// Removes ever smaller chunks of `items` as long as the rest still fails.
+fn drop_chunks<T: Clone, F: FnMut( &[T] ) -> bool>( mut items: Vec<T>, mut fails: F ) -> Vec<T> {
+    let mut chunk = items.len() / 2;
+
+    while chunk > 0 {
+        let mut start = 0;
+
+        while start < items.len() {
+            let end = (start + chunk).min( items.len() );
+            let candidate = [ &items[ .. start ], &items[ end .. ] ].concat();
+
+            if fails( &candidate ) {
+                items = candidate;
+            } else {
+                start = end;
+            }
+        }
+
+        chunk /= 2;
+    }
+
+    items
+}
+
+fn minimise( check: &Check, input: Vec<String>, timeout: Duration ) -> Vec<String> {
+    let fails = |input: &[String]| disagree( check, input, timeout ).is_some();
+
+    let mut input = drop_chunks( input, |lines| fails( lines ) );
+
+    for i in 0 .. input.len() {
+        let chars = input[ i ].chars().collect::<Vec<_>>();
+        let kept = drop_chunks( chars, |chars| {
+            let mut candidate = input.clone();
+            candidate[ i ] = chars.iter().collect();
+            fails( &candidate )
+        } );
+
+        input[ i ] = kept.into_iter().collect();
+    }
+
+    input
+}
+
+/// Runs every check on `cases` generated inputs, with sizes going round from
+/// 1 to `size` and seeds counting up from `seed`. Returns the first mismatch,
+/// already minimised, or an error when the reference itself fails on a case.
+pub fn check<G>( cases: usize, size: usize, seed: u64, generate: G, checks: &[Check] ) -> Result<Option<Mismatch>, String>
+    where G: Fn( &mut Rng, usize ) -> Vec<String>
+{
+    let size = size.max( 1 );
+
+    for case in 0 .. cases {
+        let (case_size, case_seed) = (case % size + 1, seed.wrapping_add( case as u64 ));
+        let input = generate( &mut Rng::new( case_seed ), case_size );
+
+        for check in checks {
+            let start = Instant::now();
+            let reference = solve( check.2, &input );
+            let optimised = solve( check.1, &input );
+            let timeout = Duration::from_millis( 100 ) + start.elapsed() * 10;
+
+            if let Outcome::Panic( message ) = reference {
+                return Err( format!( "reference for {} fails on size {}, seed {}: {}", check.0, case_size, case_seed, message ) );
+            }
+
+            if optimised == reference {
+                continue;
+            }
+
+            // NOTE: Smaller inputs from the same seed are always valid, so
+            // those are tried before cutting the input by hand
+            let (size, input) = (1 .. case_size)
+                                    .map( |s| (s, generate( &mut Rng::new( case_seed ), s )) )
+                                    .find( |(_, input)| disagree( check, input, timeout ).is_some() )
+                                    .unwrap_or( (case_size, input) );
+
+            let input = minimise( check, input, timeout );
+            let (optimised, reference) = disagree( check, &input, timeout ).unwrap_or( (optimised, reference) );
+
+            return Ok( Some( Mismatch { check: check.0, case, size, seed: case_seed, input, optimised, reference } ) );
+        }
+    }
+
+    Ok( None )
+}
+
+/// Handles `diff-test [cases] [size] [seed]` for a day, `size` defaults to
+/// `default_size` and should keep the references reasonably fast.
+pub fn run<G>( default_size: usize, generate: G, checks: &[Check] ) -> Result<(), Box<dyn Error>>
+    where G: Fn( &mut Rng, usize ) -> Vec<String>
+{
+    let arg = |n: usize, name: &str, default: u64| match env::args().nth( n ) {
+        Some( s ) => s.parse::<u64>().map_err( |_| format!( "invalid {}: '{}'", name, s ) ),
+        None      => Ok( default )
+    };
+
+    let cases = arg( 2, "number of cases", 100 )? as usize;
+    let size = arg( 3, "size", default_size as u64 )? as usize;
+    let seed = arg( 4, "seed", 0 )?;
+
+    // NOTE: Panics are expected while minimising, they are reported as outcomes
+    let hook = panic::take_hook();
+    panic::set_hook( Box::new( |_| {} ) );
+    let result = check( cases, size, seed, generate, checks );
+    panic::set_hook( hook );
+
+    match result? {
+        None             => {
+            println!( "{} cases, no disagreements", cases );
+            Ok( () )
+        },
+        Some( mismatch ) => {
+            println!(
+                "{} disagrees on case {} (gen {} {}), minimised input:",
+                mismatch.check, mismatch.case, mismatch.size, mismatch.seed
+            );
+
+            for line in &mismatch.input {
+                println!( "{}", line );
+            }
+
+            println!();
+            println!( "optimised: {}", mismatch.optimised );
+            println!( "reference: {}", mismatch.reference );
+
+            Err( "optimised and reference solutions disagree".into() )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numbers( rng: &mut Rng, size: usize ) -> Vec<String> {
+        (0 .. size).map( |_| rng.between( 0, 99 ).to_string() ).collect()
+    }
+
+    fn sum( input: &[String] ) -> String {
+        input.iter().map( |l| l.parse::<u64>().unwrap() ).sum::<u64>().to_string()
+    }
+
+    // Forgets about every number with a seven in it.
+    fn broken_sum( input: &[String] ) -> String {
+        input.iter().filter( |l| ! l.contains( '7' ) ).map( |l| l.parse::<u64>().unwrap() ).sum::<u64>().to_string()
+    }
+
+    fn two_digits( rng: &mut Rng, size: usize ) -> Vec<String> {
+        (0 .. size).map( |_| rng.between( 10, 99 ).to_string() ).collect()
+    }
+
+    // Hangs on the single digits which cutting lines would lead to.
+    fn picky_sum( input: &[String] ) -> String {
+        if input.iter().any( |l| l.len() < 2 ) {
+            loop { thread::sleep( Duration::from_secs( 1 ) ) }
+        }
+
+        sum( input )
+    }
+
+    #[test]
+    fn equal_solvers_agree() {
+        let checks: [Check; 1] = [ ("sum", sum, sum) ];
+        assert!( check( 50, 20, 0, numbers, &checks ).unwrap().is_none() );
+    }
+
+    #[test]
+    fn mismatch_is_minimised() {
+        let checks: [Check; 1] = [ ("sum", broken_sum, sum) ];
+        let mismatch = check( 50, 20, 0, numbers, &checks ).unwrap().unwrap();
+
+        assert_eq!( mismatch.input, vec![ "7" ] );
+        assert_eq!( mismatch.optimised, Outcome::Answer( "0".into() ) );
+        assert_eq!( mismatch.reference, Outcome::Answer( "7".into() ) );
+    }
+
+    #[test]
+    fn failing_reference_is_an_error() {
+        let checks: [Check; 1] = [ ("sum", sum, |_| panic!( "oops" )) ];
+        assert!( check( 1, 1, 0, numbers, &checks ).unwrap_err().contains( "oops" ) );
+    }
+
+    #[test]
+    fn hanging_candidates_are_skipped() {
+        let checks: [Check; 1] = [ ("sum", broken_sum, picky_sum) ];
+        let mismatch = check( 50, 20, 0, two_digits, &checks ).unwrap().unwrap();
+
+        assert_eq!( mismatch.input.len(), 1 );
+        assert_eq!( mismatch.input[ 0 ].len(), 2 );
+    }
+}
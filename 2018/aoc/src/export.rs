@@ -0,0 +1,303 @@
+//! Rendering grid states to image files.
+//!
+//! Single frames are written as PPM or PNG (picked by the file extension),
+//! sequences of frames are stitched into an animated GIF or dumped as
+//! numbered frame files.
+
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+pub type Rgb = [u8; 3];
+
+pub const BLACK: Rgb = [0x00, 0x00, 0x00];
+pub const WHITE: Rgb = [0xff, 0xff, 0xff];
+pub const GRAY: Rgb = [0x50, 0x50, 0x50];
+pub const RED: Rgb = [0xe0, 0x20, 0x20];
+pub const GREEN: Rgb = [0x30, 0xc0, 0x30];
+pub const YELLOW: Rgb = [0xf0, 0xd0, 0x20];
+
+/// Distinct-ish color for the `i`-th item, e.g. a region or a cart.
+pub fn palette( i: usize ) -> Rgb {
+    // NOTE: Stepping the hue by the golden ratio keeps neighbours apart
+    let hue = (i as f64 * 0.618_033_988_75).fract() * 6.0;
+    let (value, saturation) = (0.95, 0.65);
+
+    let chroma = value * saturation;
+    let x = chroma * (1.0 - (hue % 2.0 - 1.0).abs());
+    let m = value - chroma;
+
+    let (r, g, b) = match hue as usize {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x)
+    };
+
+    [
+        ((r + m) * 255.0) as u8,
+        ((g + m) * 255.0) as u8,
+        ((b + m) * 255.0) as u8
+    ]
+}
+
+/// Linear blend between `from` and `to`, `t` is clamped to `0.0 ..= 1.0`.
+pub fn gradient( from: Rgb, to: Rgb, t: f64 ) -> Rgb {
+    let t = t.clamp( 0.0, 1.0 );
+    let mut color = [0; 3];
+
+    for i in 0 .. 3 {
+        color[ i ] = (from[ i ] as f64 + (to[ i ] as f64 - from[ i ] as f64) * t).round() as u8;
+    }
+
+    color
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<Rgb>
+}
+
+impl Image {
+    pub fn new( width: usize, height: usize ) -> Self {
+        Image {
+            width,
+            height,
+            pixels: vec![ BLACK; width * height ]
+        }
+    }
+
+    pub fn from_fn<F: FnMut( usize, usize ) -> Rgb>( width: usize, height: usize, mut f: F ) -> Self {
+        let mut image = Self::new( width, height );
+
+        for y in 0 .. height {
+            for x in 0 .. width {
+                image.pixels[ y * width + x ] = f( x, y );
+            }
+        }
+
+        image
+    }
+
+    pub fn width( &self ) -> usize {
+        self.width
+    }
+
+    pub fn height( &self ) -> usize {
+        self.height
+    }
+
+    pub fn get( &self, x: usize, y: usize ) -> Option<Rgb> {
+        if x < self.width && y < self.height {
+            Some( self.pixels[ y * self.width + x ] )
+        } else {
+            None
+        }
+    }
+
+    /// Sets a pixel, silently ignoring coordinates outside of the image.
+    pub fn set( &mut self, x: usize, y: usize, color: Rgb ) {
+        if x < self.width && y < self.height {
+            self.pixels[ y * self.width + x ] = color;
+        }
+    }
+
+    /// Same as `set`, but for signed puzzle coordinates.
+    pub fn set_signed( &mut self, x: i64, y: i64, color: Rgb ) {
+        if x >= 0 && y >= 0 {
+            self.set( x as usize, y as usize, color );
+        }
+    }
+
+    /// Nearest-neighbour upscale, so that small grids stay readable.
+    pub fn scaled( &self, factor: usize ) -> Self {
+        Self::from_fn( self.width * factor, self.height * factor, |x, y| {
+            self.pixels[ (y / factor) * self.width + x / factor ]
+        } )
+    }
+
+    fn bytes( &self ) -> Vec<u8> {
+        self.pixels.iter().flat_map( |p| p.iter().cloned() ).collect()
+    }
+
+    pub fn write_ppm<W: Write>( &self, mut w: W ) -> Result<(), Box<dyn Error>> {
+        write!( w, "P6\n{} {}\n255\n", self.width, self.height )?;
+        w.write_all( &self.bytes() )?;
+
+        Ok( () )
+    }
+
+    pub fn write_png<W: Write>( &self, w: W ) -> Result<(), Box<dyn Error>> {
+        let mut encoder = png::Encoder::new( w, self.width as u32, self.height as u32 );
+        encoder.set_color( png::ColorType::Rgb );
+        encoder.set_depth( png::BitDepth::Eight );
+
+        encoder.write_header()?.write_image_data( &self.bytes() )?;
+
+        Ok( () )
+    }
+
+    /// Writes the image as PNG or PPM depending on the extension of `path`.
+    pub fn save<P: AsRef<Path>>( &self, path: P ) -> Result<(), Box<dyn Error>> {
+        let path = path.as_ref();
+
+        match extension( path ).as_ref() {
+            "png"   => self.write_png( BufWriter::new( File::create( path )? ) ),
+            "ppm"   => self.write_ppm( BufWriter::new( File::create( path )? ) ),
+            ext     => Err( format!( "unsupported image format: '{}'", ext ).into() )
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct Animation {
+    frames: Vec<(Image, u16)>,
+    delay: u16
+}
+
+impl Animation {
+    /// `delay` is the default time between frames in hundredths of a second.
+    pub fn new( delay: u16 ) -> Self {
+        Animation {
+            frames: vec![],
+            delay
+        }
+    }
+
+    pub fn push( &mut self, frame: Image ) {
+        let delay = self.delay;
+        self.push_delayed( frame, delay );
+    }
+
+    /// Adds a frame that stays on screen for `delay` hundredths of a second.
+    pub fn push_delayed( &mut self, frame: Image, delay: u16 ) {
+        self.frames.push( (frame, delay) );
+    }
+
+    pub fn len( &self ) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty( &self ) -> bool {
+        self.frames.is_empty()
+    }
+
+    pub fn frames( &self ) -> impl Iterator<Item=&Image> {
+        self.frames.iter().map( |(f, _)| f )
+    }
+
+    pub fn write_gif<W: Write>( &self, w: W ) -> Result<(), Box<dyn Error>> {
+        let (width, height) = match self.frames.first() {
+            Some( (f, _) )  => (f.width, f.height),
+            None            => return Err( "animation has no frames".into() )
+        };
+
+        if width > u16::MAX as usize || height > u16::MAX as usize {
+            return Err( "animation is too big for a gif".into() );
+        }
+
+        let mut encoder = gif::Encoder::new( w, width as u16, height as u16, &[] )?;
+        encoder.set_repeat( gif::Repeat::Infinite )?;
+
+        for (image, delay) in &self.frames {
+            if image.width != width || image.height != height {
+                return Err( "all frames of an animation must have the same size".into() );
+            }
+
+            let mut frame = gif::Frame::from_rgb_speed( width as u16, height as u16, &image.bytes(), 30 );
+            frame.delay = *delay;
+
+            encoder.write_frame( &frame )?;
+        }
+
+        Ok( () )
+    }
+
+    /// Writes a GIF when `path` ends with `.gif`, otherwise treats `path` as
+    /// a directory and stores every frame there as `frame_NNNNN.png`.
+    pub fn save<P: AsRef<Path>>( &self, path: P ) -> Result<(), Box<dyn Error>> {
+        let path = path.as_ref();
+
+        if extension( path ) == "gif" {
+            return self.write_gif( BufWriter::new( File::create( path )? ) );
+        }
+
+        fs::create_dir_all( path )?;
+
+        for (i, frame) in self.frames().enumerate() {
+            frame.save( path.join( format!( "frame_{:05}.png", i ) ) )?;
+        }
+
+        Ok( () )
+    }
+}
+
+fn extension( path: &Path ) -> String {
+    path.extension()
+        .map( |e| e.to_string_lossy().to_lowercase() )
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn color() -> impl Strategy<Value=Rgb> {
+        prop::array::uniform3( any::<u8>() )
+    }
+
+    proptest! {
+        #[test]
+        fn gradient_hits_both_ends( from in color(), to in color(), t in -1.0 .. 2.0f64 ) {
+            prop_assert_eq!( gradient( from, to, 0.0 ), from );
+            prop_assert_eq!( gradient( from, to, 1.0 ), to );
+
+            let mid = gradient( from, to, t );
+            for i in 0 .. 3 {
+                prop_assert!( mid[ i ] >= from[ i ].min( to[ i ] ) && mid[ i ] <= from[ i ].max( to[ i ] ) );
+            }
+        }
+
+        #[test]
+        fn scaling_repeats_pixels( width in 1 .. 10usize, height in 1 .. 10usize, factor in 1 .. 5usize ) {
+            let image = Image::from_fn( width, height, |x, y| palette( y * width + x ) );
+            let scaled = image.scaled( factor );
+
+            prop_assert_eq!( (scaled.width(), scaled.height()), (width * factor, height * factor) );
+            for y in 0 .. scaled.height() {
+                for x in 0 .. scaled.width() {
+                    prop_assert_eq!( scaled.get( x, y ), image.get( x / factor, y / factor ) );
+                }
+            }
+        }
+
+        #[test]
+        fn ppm_is_header_and_pixels( width in 0 .. 10usize, height in 0 .. 10usize, x: usize, y: usize ) {
+            let mut image = Image::new( width, height );
+            image.set( x % (width + 1), y % (height + 1), WHITE );
+
+            let mut ppm = vec![];
+            image.write_ppm( &mut ppm ).unwrap();
+
+            let header = format!( "P6\n{} {}\n255\n", width, height );
+            prop_assert!( ppm.starts_with( header.as_bytes() ) );
+            prop_assert_eq!( ppm.len(), header.len() + width * height * 3 );
+        }
+    }
+
+    #[test]
+    fn gif_needs_matching_frames() {
+        let mut animation = Animation::new( 10 );
+        assert!( animation.write_gif( vec![] ).is_err() );
+
+        animation.push( Image::new( 2, 2 ) );
+        animation.push( Image::new( 3, 2 ) );
+        assert!( animation.write_gif( vec![] ).is_err() );
+    }
+}
@@ -0,0 +1,130 @@
+//! Random puzzle inputs for stress testing and benchmarks.
+//!
+//! Every day provides a generator which turns a size and a seed into a
+//! valid input. Numbers come from a small built-in PRNG, so the same seed
+//! gives the same input on every machine and with every version of the code.
+
+use std::env;
+use std::error::Error;
+use std::io::{self, BufWriter, Write};
+
+/// SplitMix64, plenty for generating inputs.
+#[derive(Clone, Debug)]
+pub struct Rng( u64 );
+
+impl Rng {
+    pub fn new( seed: u64 ) -> Self {
+        Rng( seed )
+    }
+
+    pub fn next_u64( &mut self ) -> u64 {
+        self.0 = self.0.wrapping_add( 0x9e37_79b9_7f4a_7c15 );
+
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul( 0xbf58_476d_1ce4_e5b9 );
+        z = (z ^ (z >> 27)).wrapping_mul( 0x94d0_49bb_1331_11eb );
+        z ^ (z >> 31)
+    }
+
+    /// Number in `0 .. n`, `n` has to be positive.
+    pub fn below( &mut self, n: usize ) -> usize {
+        assert!( n > 0, "empty range" );
+        ((self.next_u64() as u128 * n as u128) >> 64) as usize
+    }
+
+    /// Number in `lo ..= hi`.
+    pub fn between( &mut self, lo: i64, hi: i64 ) -> i64 {
+        assert!( lo <= hi, "empty range" );
+        let span = (hi as i128 - lo as i128 + 1) as u128;
+        (lo as i128 + ((self.next_u64() as u128 * span) >> 64) as i128) as i64
+    }
+
+    /// `true` with probability `p`.
+    pub fn chance( &mut self, p: f64 ) -> bool {
+        ((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < p
+    }
+
+    pub fn pick<'a, T>( &mut self, items: &'a [T] ) -> &'a T {
+        &items[ self.below( items.len() ) ]
+    }
+
+    pub fn shuffle<T>( &mut self, items: &mut [T] ) {
+        for i in ( 1 .. items.len() ).rev() {
+            items.swap( i, self.below( i + 1 ) );
+        }
+    }
+}
+
+/// Handles `gen [size] [seed]` for a day: prints the lines `generate` made
+/// for the given size (`default_size` when missing) and seed (0 when missing).
+pub fn run<F>( default_size: usize, generate: F ) -> Result<(), Box<dyn Error>>
+    where F: FnOnce( &mut Rng, usize ) -> Vec<String>
+{
+    let size = match env::args().nth( 2 ) {
+        Some( s ) => s.parse::<usize>().map_err( |_| format!( "invalid size: '{}'", s ) )?,
+        None      => default_size
+    };
+
+    let seed = match env::args().nth( 3 ) {
+        Some( s ) => s.parse::<u64>().map_err( |_| format!( "invalid seed: '{}'", s ) )?,
+        None      => 0
+    };
+
+    let stdout = io::stdout();
+    let mut out = BufWriter::new( stdout.lock() );
+
+    for line in generate( &mut Rng::new( seed ), size ) {
+        writeln!( out, "{}", line )?;
+    }
+
+    out.flush()?;
+    Ok( () )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Rng;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn same_seed_same_numbers( seed: u64 ) {
+            let (mut a, mut b) = (Rng::new( seed ), Rng::new( seed ));
+
+            for _ in 0 .. 100 {
+                prop_assert_eq!( a.next_u64(), b.next_u64() );
+            }
+        }
+
+        #[test]
+        fn ranges_are_respected( seed: u64, n in 1 .. 1000usize, lo in -1000 .. 1000i64, len in 0 .. 1000i64 ) {
+            let mut rng = Rng::new( seed );
+
+            for _ in 0 .. 100 {
+                prop_assert!( rng.below( n ) < n );
+
+                let x = rng.between( lo, lo + len );
+                prop_assert!( x >= lo && x <= lo + len );
+            }
+        }
+
+        #[test]
+        fn shuffle_keeps_items( seed: u64, mut items in prop::collection::vec( any::<u8>(), 0 .. 50 ) ) {
+            let mut shuffled = items.clone();
+            Rng::new( seed ).shuffle( &mut shuffled );
+
+            items.sort();
+            shuffled.sort();
+            prop_assert_eq!( items, shuffled );
+        }
+    }
+
+    #[test]
+    fn extreme_ranges() {
+        let mut rng = Rng::new( 7 );
+
+        rng.between( i64::MIN, i64::MAX );
+        assert_eq!( rng.between( 5, 5 ), 5 );
+        assert_eq!( rng.below( 1 ), 0 );
+    }
+}
@@ -0,0 +1,171 @@
+//! Named puzzle inputs of a day, `res/inputs/<name>.txt`, each with the
+//! answers it's known to give in `res/inputs/<name>.answers`.
+//!
+//! An answers file has a `<part>: <answer>` line for every part whose answer
+//! is known, e.g. `part2: 6,4`. Blank lines and ones starting with `#` are
+//! left out, so the file may say where the input came from.
+//!
+//! Inputs made of several blank-separated parts, such as the initial row and
+//! the rules of 2018 day 12, are taken apart with [`paragraphs`],
+//! [`sections`] or [`header_body`].
+
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Single input of a day.
+#[derive(Debug)]
+pub struct Input {
+    pub name: String,
+    pub path: PathBuf,
+    /// Expected answers, in the order of the answers file.
+    pub answers: Vec<(String, String)>
+}
+
+/// Answers listed in the text of an answers file.
+pub fn parse_answers( text: &str ) -> Result<Vec<(String, String)>, String> {
+    let mut answers: Vec<(String, String)> = vec![];
+
+    for (n, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with( '#' ) {
+            continue;
+        }
+
+        let (part, answer) = match line.split_once( ':' ) {
+            Some( (part, answer) )  => (part.trim(), answer.trim()),
+            None                    => return Err( format!( "line {}: expected '<part>: <answer>'", n + 1 ) )
+        };
+
+        if part.is_empty() || answer.is_empty() {
+            return Err( format!( "line {}: expected '<part>: <answer>'", n + 1 ) );
+        }
+
+        if answers.iter().any( |(p, _)| p == part ) {
+            return Err( format!( "line {}: second answer to {}", n + 1, part ) );
+        }
+
+        answers.push( (part.to_string(), answer.to_string()) );
+    }
+
+    Ok( answers )
+}
+
+/// Every input of the day living in `dir`, ordered by name. Inputs without
+/// an answers file have no answers.
+pub fn list<P: AsRef<Path>>( dir: P ) -> Result<Vec<Input>, Box<dyn Error>> {
+    let inputs = dir.as_ref().join( "res/inputs" );
+    let mut list = vec![];
+
+    for entry in fs::read_dir( &inputs ).map_err( |e| format!( "{}: {}", inputs.display(), e ) )? {
+        let path = entry?.path();
+        if path.extension().is_none_or( |e| e != "txt" ) {
+            continue;
+        }
+
+        let name = path.file_stem().unwrap_or_default().to_string_lossy().into_owned();
+        let answers_file = path.with_extension( "answers" );
+
+        let answers = match fs::read_to_string( &answers_file ) {
+            Ok( text )  => parse_answers( &text ).map_err( |e| format!( "{}: {}", answers_file.display(), e ) )?,
+            Err( _ )    => vec![]
+        };
+
+        list.push( Input { name, path, answers } );
+    }
+
+    list.sort_by( |a, b| a.name.cmp( &b.name ) );
+    Ok( list )
+}
+
+/// Iterator over the paragraphs of the input, see [`paragraphs`].
+pub struct Paragraphs<I> {
+    lines: I
+}
+
+impl<I: Iterator<Item=String>> Iterator for Paragraphs<I> {
+    type Item = Vec<String>;
+
+    fn next( &mut self ) -> Option<Vec<String>> {
+        let mut paragraph = vec![];
+
+        for line in self.lines.by_ref() {
+            if ! line.trim().is_empty() {
+                paragraph.push( line );
+            } else if ! paragraph.is_empty() {
+                break;
+            }
+        }
+
+        Some( paragraph ).filter( |p| ! p.is_empty() )
+    }
+}
+
+/// Lines of the input split at blank lines. Runs of blank lines count as
+/// one and the ones around the input are left out.
+pub fn paragraphs<I: IntoIterator<Item=String>>( lines: I ) -> Paragraphs<I::IntoIter> {
+    Paragraphs { lines: lines.into_iter() }
+}
+
+/// Exactly `N` paragraphs, e.g. `let [rules, updates] = sections( input )?;`.
+pub fn sections<const N: usize, I: IntoIterator<Item=String>>( lines: I ) -> Result<[Vec<String>; N], String> {
+    let found = paragraphs( lines ).collect::<Vec<_>>();
+    let count = found.len();
+
+    found.try_into().map_err( |_| format!( "expected {} sections separated by blank lines, found {}", N, count ) )
+}
+
+/// Single header line and the lines below it, a blank line apart.
+pub fn header_body<I: IntoIterator<Item=String>>( lines: I ) -> Result<(String, Vec<String>), String> {
+    let mut paragraphs = paragraphs( lines );
+    let mut header = paragraphs.next().ok_or( "missing header" )?;
+
+    if header.len() != 1 {
+        return Err( format!( "expected a single header line followed by a blank line, found {} lines", header.len() ) );
+    }
+
+    Ok( (header.remove( 0 ), paragraphs.flatten().collect()) )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_answers() {
+        let text = "# From the puzzle text\npart1: 7,3\n\n  part2 :  a b  \n";
+        assert_eq!( parse_answers( text ), Ok( vec![
+            ("part1".to_string(), "7,3".to_string()),
+            ("part2".to_string(), "a b".to_string())
+        ] ) );
+
+        assert_eq!( parse_answers( "part1 7,3" ), Err( "line 1: expected '<part>: <answer>'".into() ) );
+        assert_eq!( parse_answers( "part1:" ), Err( "line 1: expected '<part>: <answer>'".into() ) );
+        assert_eq!( parse_answers( "part1: 1\npart1: 2" ), Err( "line 2: second answer to part1".into() ) );
+    }
+
+    fn lines( text: &str ) -> Vec<String> {
+        text.lines().map( String::from ).collect()
+    }
+
+    #[test]
+    fn splits_paragraphs() {
+        let text = "\n\na\nb\n\n  \n\nc\n\n";
+
+        assert_eq!( paragraphs( lines( text ) ).collect::<Vec<_>>(), vec![ lines( "a\nb" ), lines( "c" ) ] );
+        assert_eq!( sections::<2, _>( lines( text ) ), Ok( [ lines( "a\nb" ), lines( "c" ) ] ) );
+        assert!( sections::<3, _>( lines( text ) ).is_err() );
+        assert_eq!( paragraphs( lines( "" ) ).count(), 0 );
+    }
+
+    #[test]
+    fn splits_header_from_body() {
+        let (header, body) = header_body( lines( "initial state: #.#\n\n..#.. => #\n.#... => .\n" ) ).unwrap();
+
+        assert_eq!( header, "initial state: #.#" );
+        assert_eq!( body, lines( "..#.. => #\n.#... => ." ) );
+        assert_eq!( header_body( lines( "header\n" ) ), Ok( ("header".into(), vec![]) ) );
+        assert!( header_body( lines( "header\n..#.. => #" ) ).is_err() );
+        assert!( header_body( lines( "\n\n" ) ).is_err() );
+    }
+}
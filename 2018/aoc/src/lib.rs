@@ -0,0 +1,13 @@
+//! Helpers shared between the daily solutions.
+
+pub mod big;
+pub mod config;
+pub mod diff;
+pub mod export;
+pub mod gen;
+pub mod inputs;
+pub mod mem;
+pub mod repl;
+pub mod sim;
+pub mod trace;
+pub mod viz;
@@ -0,0 +1,190 @@
+// Runs a single day with the given command, e.g. `aoc part1 2018 3` or
+// `aoc viz 2018 13`. `aoc fuzz 2018 3 claim` runs one of the day's fuzz
+// targets instead. `aoc verify 2018 13 [names...]` checks the answers of
+// every input in the day's res/inputs, or just the named ones. Set AOC_ROOT
+// to point at another checkout. `-v` and `-vv` turn on the day's trace
+// points, `--log-file <path>` sends them to a file instead of stderr.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{self, Command, ExitStatus, Stdio};
+
+use aoc::inputs;
+use aoc::trace;
+
+fn usage() -> &'static str {
+    "usage: aoc [-v|-vv] [--log-file <path>] <command> <year> <day> [args...]"
+}
+
+fn root() -> PathBuf {
+    env::var_os( "AOC_ROOT" )
+        .map( PathBuf::from )
+        .unwrap_or_else( || PathBuf::from( env!( "CARGO_MANIFEST_DIR" ) ).join( "../.." ) )
+}
+
+// NOTE: cargo-fuzz needs a nightly toolchain. `res/inputs` is passed as a second,
+// read-only corpus, so every run starts from the real puzzle inputs.
+fn fuzz( dir: &Path, args: &[String] ) -> Result<ExitStatus, Box<dyn std::error::Error>> {
+    let target = args.first().ok_or( "usage: aoc fuzz <year> <day> <target> [args...]" )?;
+    let corpus = Path::new( "fuzz/corpus" ).join( target );
+
+    fs::create_dir_all( dir.join( &corpus ) )?;
+
+    let status = Command::new( "cargo" )
+                    .args( ["+nightly", "fuzz", "run"] )
+                    .arg( target )
+                    .arg( &corpus )
+                    .arg( "res/inputs" )
+                    .args( &args[ 1.. ] )
+                    .current_dir( dir )
+                    .status()?;
+
+    Ok( status )
+}
+
+// NOTE: Every day builds on stable, so one that pins a nightly toolchain or
+// turns on unstable features is turned away before cargo gets to fail on it
+fn needs_nightly( dir: &Path ) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    for name in ["rust-toolchain", "rust-toolchain.toml"] {
+        let path = dir.join( name );
+
+        if path.is_file() && fs::read_to_string( &path )?.contains( "nightly" ) {
+            return Ok( Some( format!( "{} pins a nightly toolchain", name ) ) );
+        }
+    }
+
+    let mut dirs = vec![ dir.join( "src" ) ];
+
+    while let Some( current ) = dirs.pop() {
+        for entry in fs::read_dir( current )? {
+            let path = entry?.path();
+
+            if path.is_dir() {
+                dirs.push( path );
+            } else if path.extension().is_some_and( |e| e == "rs" ) {
+                let source = fs::read_to_string( &path )?;
+
+                if source.lines().any( |l| l.trim_start().starts_with( "#![feature" ) ) {
+                    let file = path.strip_prefix( dir ).unwrap_or( &path ).display();
+                    return Ok( Some( format!( "{} uses unstable features", file ) ) );
+                }
+            }
+        }
+    }
+
+    Ok( None )
+}
+
+// Command running the day in `dir` through cargo, with the trace flags passed on
+fn day_command( dir: &Path, flags: &trace::Flags ) -> Result<Command, Box<dyn std::error::Error>> {
+    let mut cargo = Command::new( env::var( "CARGO" ).unwrap_or_else( |_| "cargo".into() ) );
+    cargo
+        .args( ["run", "--release", "--quiet", "--"] )
+        .current_dir( dir );
+
+    if flags.verbosity > 0 {
+        cargo.env( "AOC_LOG", flags.verbosity.to_string() );
+    }
+
+    // NOTE: The day runs in its own directory, relative paths are ours
+    if let Some( log_file ) = &flags.log_file {
+        cargo.env( "AOC_LOG_FILE", env::current_dir()?.join( log_file ) );
+    }
+
+    Ok( cargo )
+}
+
+// NOTE: The answer of a part is the last line it prints, day 10 draws its
+// message above it. verify.toml goes last in AOC_CONFIG, so whatever the
+// format is set to, answers come out as plain text.
+fn verify( dir: &Path, flags: &trace::Flags, names: &[String] ) -> Result<bool, Box<dyn std::error::Error>> {
+    let inputs = inputs::list( dir )?
+                    .into_iter()
+                    .filter( |i| names.is_empty() || names.contains( &i.name ) )
+                    .collect::<Vec<_>>();
+
+    if let Some( name ) = names.iter().find( |n| ! inputs.iter().any( |i| &i.name == *n ) ) {
+        return Err( format!( "no input named {} in {}", name, dir.join( "res/inputs" ).display() ).into() );
+    }
+
+    let mut config = env::var_os( "AOC_CONFIG" ).map( |c| env::split_paths( &c ).collect::<Vec<_>>() ).unwrap_or_default();
+    config.push( Path::new( env!( "CARGO_MANIFEST_DIR" ) ).join( "verify.toml" ) );
+    let config = env::join_paths( config )?;
+
+    let width = inputs.iter().map( |i| i.name.len() ).max().unwrap_or( 0 );
+    let (mut checked, mut correct) = (0, 0);
+
+    for input in &inputs {
+        if input.answers.is_empty() {
+            println!( "{:<width$}  no answers", input.name, width = width );
+        }
+
+        for (part, expected) in &input.answers {
+            let output = day_command( dir, flags )?
+                            .arg( part )
+                            .arg( &input.path )
+                            .env( "AOC_CONFIG", &config )
+                            .stderr( Stdio::inherit() )
+                            .output()?;
+
+            let stdout = String::from_utf8_lossy( &output.stdout );
+            let answer = stdout.lines().rev().find( |l| ! l.trim().is_empty() ).unwrap_or( "" ).trim();
+
+            let verdict = match (output.status.success(), answer == expected) {
+                (false, _)      => format!( "FAILED: exited with {}", output.status ),
+                (true, false)   => format!( "WRONG: expected {}, got {}", expected, answer ),
+                (true, true)    => "ok".to_string()
+            };
+
+            checked += 1;
+            correct += (verdict == "ok") as usize;
+            println!( "{:<width$}  {}  {}", input.name, part, verdict, width = width );
+        }
+    }
+
+    println!( "{} of {} answers correct", correct, checked );
+    Ok( correct == checked )
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut args = env::args().skip( 1 ).collect::<Vec<_>>();
+    let flags = trace::take_flags( &mut args )?;
+
+    if args.len() < 3 {
+        return Err( usage().into() );
+    }
+
+    let (command, year) = (&args[ 0 ], &args[ 1 ]);
+    let day = args[ 2 ].parse::<usize>().map_err( |_| usage() )?;
+
+    let dir = root().join( year ).join( format!( "day{}", day ) );
+    if ! dir.join( "Cargo.toml" ).is_file() {
+        return Err( format!( "no solution for day {} of {}", day, year ).into() );
+    }
+
+    if command == "fuzz" {
+        if ! dir.join( "fuzz/Cargo.toml" ).is_file() {
+            return Err( format!( "no fuzz targets for day {} of {}", day, year ).into() );
+        }
+
+        let status = fuzz( &dir, &args[ 3.. ] )?;
+        process::exit( status.code().unwrap_or( 1 ) );
+    }
+
+    if let Some( reason ) = needs_nightly( &dir )? {
+        return Err( format!( "day {} of {} needs a nightly toolchain: {}", day, year, reason ).into() );
+    }
+
+    if command == "verify" {
+        let passed = verify( &dir, &flags, &args[ 3.. ] )?;
+        process::exit( if passed { 0 } else { 1 } );
+    }
+
+    let status = day_command( &dir, &flags )?
+                    .arg( command )
+                    .args( &args[ 3.. ] )
+                    .status()?;
+
+    process::exit( status.code().unwrap_or( 1 ) );
+}
@@ -0,0 +1,157 @@
+//! Allocation counting for `--profile-mem`.
+//!
+//! A day opts in by installing [`Counting`] as its global allocator, after
+//! which [`profile`] reports how much every phase of a solution allocated.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::error::Error;
+use std::fmt::Display;
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new( 0 );
+static ALLOCATED: AtomicUsize = AtomicUsize::new( 0 );
+static LIVE: AtomicUsize = AtomicUsize::new( 0 );
+static PEAK: AtomicUsize = AtomicUsize::new( 0 );
+
+/// System allocator which keeps count of what goes through it.
+pub struct Counting;
+
+impl Counting {
+    fn allocated( size: usize ) {
+        ALLOCATIONS.fetch_add( 1, Ordering::Relaxed );
+        ALLOCATED.fetch_add( size, Ordering::Relaxed );
+
+        let live = LIVE.fetch_add( size, Ordering::Relaxed ) + size;
+        PEAK.fetch_max( live, Ordering::Relaxed );
+    }
+
+    fn freed( size: usize ) {
+        LIVE.fetch_sub( size, Ordering::Relaxed );
+    }
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc( &self, layout: Layout ) -> *mut u8 {
+        let ptr = System.alloc( layout );
+        if ! ptr.is_null() {
+            Self::allocated( layout.size() );
+        }
+
+        ptr
+    }
+
+    unsafe fn alloc_zeroed( &self, layout: Layout ) -> *mut u8 {
+        let ptr = System.alloc_zeroed( layout );
+        if ! ptr.is_null() {
+            Self::allocated( layout.size() );
+        }
+
+        ptr
+    }
+
+    unsafe fn dealloc( &self, ptr: *mut u8, layout: Layout ) {
+        System.dealloc( ptr, layout );
+        Self::freed( layout.size() );
+    }
+
+    // NOTE: A reallocation counts as a new allocation of the whole new size
+    unsafe fn realloc( &self, ptr: *mut u8, layout: Layout, new_size: usize ) -> *mut u8 {
+        let new = System.realloc( ptr, layout, new_size );
+        if ! new.is_null() {
+            Self::freed( layout.size() );
+            Self::allocated( new_size );
+        }
+
+        new
+    }
+}
+
+/// What a piece of code allocated.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Stats {
+    /// Number of allocations, reallocations included.
+    pub allocations: usize,
+    /// Total of all allocated bytes, whether freed since or not.
+    pub allocated: usize,
+    /// Most bytes alive at once.
+    pub peak: usize
+}
+
+// Current counters, the peak starts over from whatever is alive right now.
+fn start() -> (usize, usize) {
+    PEAK.store( LIVE.load( Ordering::Relaxed ), Ordering::Relaxed );
+    (ALLOCATIONS.load( Ordering::Relaxed ), ALLOCATED.load( Ordering::Relaxed ))
+}
+
+fn since( (allocations, allocated): (usize, usize) ) -> Stats {
+    Stats {
+        allocations: ALLOCATIONS.load( Ordering::Relaxed ) - allocations,
+        allocated: ALLOCATED.load( Ordering::Relaxed ) - allocated,
+        peak: PEAK.load( Ordering::Relaxed )
+    }
+}
+
+/// Runs `f`, returning its result along with what it allocated.
+pub fn measure<R, F: FnOnce() -> R>( f: F ) -> (R, Stats) {
+    let started = start();
+    let result = f();
+
+    (result, since( started ))
+}
+
+/// Handles `--profile-mem [input]` for a day: reading and parsing the input,
+/// part one and part two are measured one after another.
+pub fn profile<T, P, S1, S2, R1, R2>( input_file: &str, parse: P, part1: S1, part2: S2 ) -> Result<(), Box<dyn Error>>
+    where P: FnOnce( std::vec::IntoIter<String> ) -> T,
+          S1: FnOnce( &T ) -> R1,
+          S2: FnOnce( &T ) -> R2,
+          R1: Display,
+          R2: Display
+{
+    let (input, parsing) = measure( || -> Result<T, Box<dyn Error>> {
+        let reader = BufReader::new( fs::File::open( input_file )? );
+        let lines = reader.lines().collect::<Result<Vec<_>, _>>()?;
+
+        Ok( parse( lines.into_iter() ) )
+    } );
+
+    let input = input?;
+    let (first, first_stats) = measure( || part1( &input ).to_string() );
+    let (second, second_stats) = measure( || part2( &input ).to_string() );
+
+    println!( "part1: {}", first );
+    println!( "part2: {}", second );
+    println!();
+    println!( "{:<8}{:>14}{:>16}{:>16}", "phase", "allocations", "allocated", "peak" );
+
+    for (phase, stats) in &[ ("parse", parsing), ("part1", first_stats), ("part2", second_stats) ] {
+        println!( "{:<8}{:>14}{:>16}{:>16}", phase, stats.allocations, stats.allocated, stats.peak );
+    }
+
+    Ok( () )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // NOTE: The test binary doesn't use `Counting` as its allocator, so only
+    // the calls made here are counted
+    #[test]
+    fn counts_allocations_and_peak() {
+        let layout = Layout::from_size_align( 1000, 8 ).unwrap();
+
+        let ((), stats) = measure( || unsafe {
+            let a = Counting.alloc( layout );
+            let b = Counting.alloc_zeroed( layout );
+            Counting.dealloc( a, layout );
+
+            let b = Counting.realloc( b, layout, 3000 );
+            Counting.dealloc( b, Layout::from_size_align( 3000, 8 ).unwrap() );
+        } );
+
+        assert_eq!( stats, Stats { allocations: 3, allocated: 5000, peak: 3000 } );
+    }
+}
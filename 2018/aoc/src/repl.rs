@@ -0,0 +1,206 @@
+//! Interactive prompt for poking at a day's parsed input.
+//!
+//! A day lists its commands, each with the arguments it takes and a line of
+//! help, and [`run`] hands them the parsed input. Lines can be edited, old
+//! ones come back with the arrow keys (in later sessions too) and `tab`
+//! completes command names. `help` lists the commands, `quit` or `^D` leaves.
+
+use std::error::Error;
+use std::path::Path;
+use std::str::FromStr;
+
+use rustyline::completion::Completer;
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::Validator;
+use rustyline::{Context, Editor, Helper};
+
+/// Single command over the day's parsed input of type `T`.
+pub struct Command<T> {
+    pub name: &'static str,
+    /// Arguments as shown in the help, `<required>` ones first and then
+    /// `[optional]` ones.
+    pub args: &'static str,
+    pub help: &'static str,
+    pub run: fn( &T, &[&str] ) -> Result<String, String>
+}
+
+impl<T> Command<T> {
+    fn usage( &self ) -> String {
+        format!( "{} {}", self.name, self.args ).trim_end().to_string()
+    }
+}
+
+/// Argument `i` parsed as a `T`, `name` is what the error calls it.
+pub fn arg<T: FromStr>( args: &[&str], i: usize, name: &str ) -> Result<T, String> {
+    let arg = args.get( i ).ok_or_else( || format!( "missing <{}>", name ) )?;
+    arg.parse().map_err( |_| format!( "invalid <{}>: '{}'", name, arg ) )
+}
+
+/// Like `arg`, but gives `default` when the argument is left out.
+pub fn arg_or<T: FromStr>( args: &[&str], i: usize, name: &str, default: T ) -> Result<T, String> {
+    match args.get( i ) {
+        Some( _ )   => arg( args, i, name ),
+        None        => Ok( default )
+    }
+}
+
+fn help<T>( commands: &[Command<T>] ) -> String {
+    let width = commands.iter().map( |c| c.usage().len() ).max().unwrap_or( 0 ).max( 4 );
+
+    commands
+        .iter()
+        .map( |c| (c.usage(), c.help) )
+        .chain( vec![ ("help".to_string(), "this list"), ("quit".to_string(), "leaves") ] )
+        .map( |(usage, help)| format!( "{:<width$}  {}", usage, help, width = width ) )
+        .collect::<Vec<_>>()
+        .join( "\n" )
+}
+
+/// Runs a single line, giving back what should be printed.
+pub fn eval<T>( state: &T, commands: &[Command<T>], line: &str ) -> Result<String, String> {
+    let words = line.split_whitespace().collect::<Vec<_>>();
+    let (name, args) = match words.split_first() {
+        Some( (name, args) )    => (*name, args),
+        None                    => return Ok( String::new() )
+    };
+
+    if name == "help" {
+        return Ok( help( commands ) );
+    }
+
+    let command = commands
+                    .iter()
+                    .find( |c| c.name == name )
+                    .ok_or_else( || format!( "unknown command '{}', try help", name ) )?;
+
+    let required = command.args.matches( '<' ).count();
+    let optional = command.args.matches( '[' ).count();
+
+    if args.len() < required || args.len() > required + optional {
+        return Err( format!( "usage: {}", command.usage() ) );
+    }
+
+    (command.run)( state, args )
+}
+
+struct Names(Vec<&'static str>);
+
+impl Completer for Names {
+    type Candidate = String;
+
+    // NOTE: Only the first word is a command, arguments aren't completed
+    fn complete( &self, line: &str, pos: usize, _: &Context ) -> rustyline::Result<(usize, Vec<String>)> {
+        let word = &line[ .. pos ];
+        if word.contains( char::is_whitespace ) {
+            return Ok( (pos, vec![]) );
+        }
+
+        Ok( (0, self.0.iter().filter( |n| n.starts_with( word ) ).map( |n| n.to_string() ).collect()) )
+    }
+}
+
+impl Hinter for Names {
+    type Hint = String;
+}
+
+impl Highlighter for Names {}
+impl Validator for Names {}
+impl Helper for Names {}
+
+/// Prompts for commands until told to quit, keeping the history in
+/// `.repl-history` of the day living in `dir`.
+pub fn run<T, P: AsRef<Path>>( state: &T, commands: &[Command<T>], dir: P ) -> Result<(), Box<dyn Error>> {
+    let history = dir.as_ref().join( ".repl-history" );
+    let mut names = commands.iter().map( |c| c.name ).collect::<Vec<_>>();
+    names.extend( ["help", "quit"] );
+
+    let mut editor = Editor::<Names, DefaultHistory>::new()?;
+    editor.set_helper( Some( Names( names ) ) );
+
+    // NOTE: There's no history on the first run
+    let _ = editor.load_history( &history );
+
+    loop {
+        let line = match editor.readline( "> " ) {
+            Ok( line )                                                  => line,
+            Err( ReadlineError::Eof ) | Err( ReadlineError::Interrupted ) => break,
+            Err( e )                                                    => return Err( e.into() )
+        };
+
+        if ! line.trim().is_empty() {
+            editor.add_history_entry( line.as_str() )?;
+        }
+
+        if ["quit", "exit"].contains( &line.trim() ) {
+            break;
+        }
+
+        match eval( state, commands, &line ) {
+            Ok( output ) if output.is_empty()   => {},
+            Ok( output )                        => println!( "{}", output ),
+            Err( e )                            => println!( "error: {}", e )
+        }
+    }
+
+    editor.save_history( &history )?;
+    Ok( () )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COMMANDS: [Command<Vec<i64>>; 2] = [
+        Command {
+            name: "get",
+            args: "<index>",
+            help: "number at <index>",
+            run: |numbers, args| {
+                let i = arg::<usize>( args, 0, "index" )?;
+                numbers.get( i ).map( |n| n.to_string() ).ok_or_else( || "out of range".into() )
+            }
+        },
+        Command {
+            name: "sum",
+            args: "[from] [to]",
+            help: "sum of the numbers",
+            run: |numbers, args| {
+                let from = arg_or( args, 0, "from", 0 )?;
+                let to = arg_or( args, 1, "to", numbers.len() )?;
+
+                Ok( numbers[ from .. to ].iter().sum::<i64>().to_string() )
+            }
+        }
+    ];
+
+    #[test]
+    fn runs_commands_with_checked_arguments() {
+        let numbers = vec![ 4, 8, 15, 16 ];
+        let eval = |line| eval( &numbers, &COMMANDS, line );
+
+        assert_eq!( eval( "get 2" ), Ok( "15".into() ) );
+        assert_eq!( eval( "  sum  1 " ), Ok( "39".into() ) );
+        assert_eq!( eval( "sum" ), Ok( "43".into() ) );
+        assert_eq!( eval( "" ), Ok( "".into() ) );
+        assert_eq!( eval( "get 7" ), Err( "out of range".into() ) );
+        assert_eq!( eval( "get x" ), Err( "invalid <index>: 'x'".into() ) );
+        assert_eq!( eval( "get" ), Err( "usage: get <index>".into() ) );
+        assert_eq!( eval( "sum 1 2 3" ), Err( "usage: sum [from] [to]".into() ) );
+        assert!( eval( "set 1" ).is_err() );
+        assert!( eval( "help" ).unwrap().lines().any( |l| l.starts_with( "sum [from] [to]" ) ) );
+    }
+
+    #[test]
+    fn completes_command_names() {
+        let names = Names( vec![ "power", "print", "quit" ] );
+        let history = DefaultHistory::new();
+        let context = Context::new( &history );
+
+        assert_eq!( names.complete( "pr", 2, &context ).unwrap(), (0, vec![ "print".to_string() ]) );
+        assert_eq!( names.complete( "p", 1, &context ).unwrap().1.len(), 2 );
+        assert!( names.complete( "power 3", 7, &context ).unwrap().1.is_empty() );
+    }
+}
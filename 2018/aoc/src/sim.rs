@@ -0,0 +1,219 @@
+//! Step based simulations.
+//!
+//! A puzzle which advances its state tick by tick implements [`Simulation`]
+//! and is then driven by a [`Runner`], which keeps track of the current tick
+//! and can optionally record a trace of snapshots to rewind and replay.
+
+use std::collections::VecDeque;
+
+/// State that can be advanced one tick at a time.
+pub trait Simulation {
+    type Snapshot: Clone;
+
+    /// Advances the simulation by a single tick.
+    fn step( &mut self );
+
+    /// Captures everything that `step` can change.
+    fn snapshot( &self ) -> Self::Snapshot;
+
+    /// Brings the simulation back to a previously captured state.
+    fn restore( &mut self, snapshot: &Self::Snapshot );
+}
+
+pub struct Runner<S: Simulation> {
+    sim: S,
+    tick: usize,
+    trace_start: usize,
+    trace: Option<VecDeque<S::Snapshot>>,
+    capacity: usize
+}
+
+impl<S: Simulation> Runner<S> {
+    pub fn new( sim: S ) -> Self {
+        Runner {
+            sim,
+            tick: 0,
+            trace_start: 0,
+            trace: None,
+            capacity: usize::MAX
+        }
+    }
+
+    /// Number of ticks simulated so far.
+    pub fn tick( &self ) -> usize {
+        self.tick
+    }
+
+    pub fn sim( &self ) -> &S {
+        &self.sim
+    }
+
+    pub fn sim_mut( &mut self ) -> &mut S {
+        &mut self.sim
+    }
+
+    pub fn into_inner( self ) -> S {
+        self.sim
+    }
+
+    /// Starts recording a snapshot after every tick, beginning with the
+    /// current one. Any previously recorded trace is dropped.
+    pub fn record( &mut self ) {
+        self.record_last( usize::MAX );
+    }
+
+    /// Like `record`, but only keeps the `capacity` most recent snapshots.
+    pub fn record_last( &mut self, capacity: usize ) {
+        self.trace_start = self.tick;
+        self.trace = Some( VecDeque::from( vec![ self.sim.snapshot() ] ) );
+        self.capacity = capacity.max( 1 );
+    }
+
+    pub fn is_recording( &self ) -> bool {
+        self.trace.is_some()
+    }
+
+    /// Recorded snapshots paired with the tick they were taken at.
+    pub fn trace( &self ) -> impl Iterator<Item=(usize, &S::Snapshot)> {
+        let start = self.trace_start;
+
+        self.trace
+            .iter()
+            .flatten()
+            .enumerate()
+            .map( move |(i, s)| (start + i, s) )
+    }
+
+    pub fn step( &mut self ) {
+        self.sim.step();
+        self.tick += 1;
+
+        if let Some( trace ) = &mut self.trace {
+            if trace.len() == self.capacity {
+                trace.pop_front();
+                self.trace_start += 1;
+            }
+
+            trace.push_back( self.sim.snapshot() );
+        }
+    }
+
+    pub fn step_n( &mut self, ticks: usize ) {
+        for _ in 0 .. ticks {
+            self.step();
+        }
+    }
+
+    /// Steps until `done` holds (checking the current state first) and
+    /// returns the tick at which it happened.
+    pub fn run_until<P: FnMut( &S ) -> bool>( &mut self, mut done: P ) -> usize {
+        while ! done( &self.sim ) {
+            self.step();
+        }
+
+        self.tick
+    }
+
+    /// Goes back `ticks` ticks, see [`Runner::seek`].
+    pub fn rewind( &mut self, ticks: usize ) -> bool {
+        match self.tick.checked_sub( ticks ) {
+            Some( tick ) => self.seek( tick ),
+            None         => false
+        }
+    }
+
+    /// Moves the simulation to `tick`. Past ticks are restored from the
+    /// recorded trace, future ones are simulated. Returns `false` (leaving
+    /// the state untouched) when a past tick wasn't recorded.
+    pub fn seek( &mut self, tick: usize ) -> bool {
+        if tick >= self.tick {
+            self.step_n( tick - self.tick );
+            return true;
+        }
+
+        let start = self.trace_start;
+        let trace = match &mut self.trace {
+            Some( trace ) if tick >= start => trace,
+            _                              => return false
+        };
+
+        // NOTE: Simulations are deterministic, so everything after `tick`
+        // will be recorded again once we step forward.
+        trace.truncate( tick - start + 1 );
+        self.sim.restore( &trace[ tick - start ] );
+        self.tick = tick;
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    // Collatz-like walk, so a restored state can't be guessed from the tick
+    struct Walk( u64 );
+
+    impl Simulation for Walk {
+        type Snapshot = u64;
+
+        fn step( &mut self ) {
+            self.0 = if self.0.is_multiple_of( 2 ) { self.0 / 2 + 7 } else { self.0 * 3 + 1 };
+        }
+
+        fn snapshot( &self ) -> u64 {
+            self.0
+        }
+
+        fn restore( &mut self, snapshot: &u64 ) {
+            self.0 = *snapshot;
+        }
+    }
+
+    fn walk( start: u64, ticks: usize ) -> u64 {
+        let mut sim = Walk( start );
+        (0 .. ticks).for_each( |_| sim.step() );
+        sim.0
+    }
+
+    proptest! {
+        #[test]
+        fn seek_matches_fresh_run( start in 1 .. 1000u64, moves in prop::collection::vec( 0 .. 100usize, 1 .. 10 ) ) {
+            let mut runner = Runner::new( Walk( start ) );
+            runner.record();
+
+            for tick in moves {
+                prop_assert!( runner.seek( tick ) );
+                prop_assert_eq!( runner.tick(), tick );
+                prop_assert_eq!( runner.sim().0, walk( start, tick ) );
+            }
+        }
+
+        #[test]
+        fn trace_keeps_last_snapshots( start in 1 .. 1000u64, capacity in 1 .. 20usize, ticks in 0 .. 50usize ) {
+            let mut runner = Runner::new( Walk( start ) );
+            runner.record_last( capacity );
+            runner.step_n( ticks );
+
+            let trace = runner.trace().map( |(t, &s)| (t, s) ).collect::<Vec<_>>();
+            let first = (ticks + 1).saturating_sub( capacity );
+            let expected = (first ..= ticks).map( |t| (t, walk( start, t )) ).collect::<Vec<_>>();
+
+            prop_assert_eq!( trace, expected );
+            prop_assert_eq!( runner.rewind( ticks - first ), true );
+            prop_assert_eq!( runner.rewind( 1 ), false );
+            prop_assert_eq!( runner.sim().0, walk( start, first ) );
+        }
+    }
+
+    #[test]
+    fn rewind_needs_a_trace() {
+        let mut runner = Runner::new( Walk( 5 ) );
+        runner.step_n( 3 );
+
+        assert!( ! runner.rewind( 1 ) );
+        assert_eq!( runner.tick(), 3 );
+        assert_eq!( runner.run_until( |w| w.0 == walk( 5, 7 ) ), 7 );
+    }
+}
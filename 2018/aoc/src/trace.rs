@@ -0,0 +1,137 @@
+//! Leveled trace points, for when an answer comes out wrong.
+//!
+//! Days log through [`info!`](crate::info) and [`debug!`](crate::debug),
+//! which stay silent unless `AOC_LOG` is set to `1` (info) or `2` (debug).
+//! Traces go to stderr, or to the file named by `AOC_LOG_FILE`, so the
+//! answers on stdout stay clean. The `aoc` runner sets both from its `-v`,
+//! `-vv` and `--log-file <path>` flags.
+
+use std::env;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, LineWriter, Write};
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+
+/// How much detail a trace point carries.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    /// Notable events, shown with `-v`.
+    Info = 1,
+    /// Every little step, shown with `-vv`.
+    Debug = 2
+}
+
+struct Sink {
+    verbosity: usize,
+    out: Mutex<Box<dyn Write + Send>>
+}
+
+static SINK: OnceLock<Sink> = OnceLock::new();
+
+fn sink() -> &'static Sink {
+    SINK.get_or_init( || {
+        let verbosity = env::var( "AOC_LOG" ).ok().and_then( |v| v.parse().ok() ).unwrap_or( 0 );
+        let out: Box<dyn Write + Send> = match env::var_os( "AOC_LOG_FILE" ).map( File::create ) {
+            Some( Ok( file ) )  => Box::new( LineWriter::new( file ) ),
+            Some( Err( e ) )    => {
+                eprintln!( "can't open the log file, tracing to stderr: {}", e );
+                Box::new( io::stderr() )
+            },
+            None                => Box::new( io::stderr() )
+        };
+
+        Sink { verbosity, out: Mutex::new( out ) }
+    } )
+}
+
+/// Whether trace points of the given level are shown.
+pub fn enabled( level: Level ) -> bool {
+    sink().verbosity >= level as usize
+}
+
+#[doc(hidden)]
+pub fn write( level: Level, args: fmt::Arguments ) {
+    let tag = match level {
+        Level::Info     => "INFO ",
+        Level::Debug    => "DEBUG"
+    };
+
+    // NOTE: A log which can't be written shouldn't take the answer down with it
+    let mut out = sink().out.lock().unwrap_or_else( |e| e.into_inner() );
+    let _ = writeln!( out, "{} {}", tag, args );
+}
+
+/// Traces a notable event, like `println!`.
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)*) => {
+        if $crate::trace::enabled( $crate::trace::Level::Info ) {
+            $crate::trace::write( $crate::trace::Level::Info, format_args!( $($arg)* ) );
+        }
+    }
+}
+
+/// Traces a detail, like `println!`.
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)*) => {
+        if $crate::trace::enabled( $crate::trace::Level::Debug ) {
+            $crate::trace::write( $crate::trace::Level::Debug, format_args!( $($arg)* ) );
+        }
+    }
+}
+
+/// Tracing flags given on the command line.
+#[derive(Debug, Default, PartialEq)]
+pub struct Flags {
+    pub verbosity: usize,
+    pub log_file: Option<PathBuf>
+}
+
+/// Takes `-v`, `-vv` (each `v` counts) and `--log-file <path>` out of `args`,
+/// wherever they are, leaving everything else in order.
+pub fn take_flags( args: &mut Vec<String> ) -> Result<Flags, String> {
+    let mut flags = Flags::default();
+    let mut rest = vec![];
+    let mut iter = args.drain( .. );
+
+    while let Some( arg ) = iter.next() {
+        if arg.len() > 1 && arg.starts_with( '-' ) && arg[ 1 .. ].chars().all( |c| c == 'v' ) {
+            flags.verbosity += arg.len() - 1;
+        } else if arg == "--log-file" {
+            flags.log_file = Some( iter.next().ok_or( "--log-file needs a path" )?.into() );
+        } else {
+            rest.push( arg );
+        }
+    }
+
+    drop( iter );
+    *args = rest;
+
+    Ok( flags )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args( s: &str ) -> Vec<String> {
+        s.split_whitespace().map( String::from ).collect()
+    }
+
+    #[test]
+    fn takes_flags_from_anywhere() {
+        let mut rest = args( "-v part1 2018 --log-file trace.log 4 -v input" );
+        let flags = take_flags( &mut rest ).unwrap();
+
+        assert_eq!( flags, Flags { verbosity: 2, log_file: Some( "trace.log".into() ) } );
+        assert_eq!( rest, args( "part1 2018 4 input" ) );
+
+        let mut rest = args( "-vv gen 2018 7 -5" );
+        assert_eq!( take_flags( &mut rest ).unwrap().verbosity, 2 );
+        assert_eq!( rest, args( "gen 2018 7 -5" ) );
+
+        assert!( take_flags( &mut args( "part1 2018 4 --log-file" ) ).is_err() );
+    }
+}
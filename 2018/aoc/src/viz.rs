@@ -0,0 +1,168 @@
+//! Interactive terminal viewer for step based simulations.
+//!
+//! Keys: `space` pauses, `s`/`→` steps forward, `b`/`←` steps back,
+//! `+`/`-` change the speed, `n` jumps to the next event, `h`/`j`/`k`/`l`
+//! pan the view and `q` quits.
+
+use std::error::Error;
+use std::io::{self, Write};
+use std::time::Duration;
+
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{execute, queue};
+
+use crate::sim::{Runner, Simulation};
+
+/// Upper bound on ticks simulated while looking for the next event.
+const MAX_JUMP: usize = 1_000_000;
+
+/// How many ticks can be stepped back.
+const HISTORY: usize = 1000;
+
+pub trait Visualize: Simulation {
+    /// Draws the current state as lines of text. `width` and `height` are
+    /// the size of the view, larger drawings are cropped by the viewer.
+    fn render( &self, width: usize, height: usize ) -> Vec<String>;
+
+    /// Describes what noteworthy happened in the last tick, if anything
+    /// (e.g. a collision). Used to jump between interesting ticks.
+    fn event( &self ) -> Option<String> {
+        None
+    }
+}
+
+// NOTE: Restores the terminal even when the viewer bails out early
+struct Screen;
+
+impl Screen {
+    fn enter() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        execute!( io::stdout(), EnterAlternateScreen, Hide )?;
+
+        Ok( Screen )
+    }
+}
+
+impl Drop for Screen {
+    fn drop( &mut self ) {
+        let _ = execute!( io::stdout(), Show, LeaveAlternateScreen );
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+struct View {
+    paused: bool,
+    delay: u64,
+    pan: (usize, usize),
+    message: String
+}
+
+fn draw<S: Visualize>( runner: &Runner<S>, view: &View ) -> io::Result<()> {
+    let (width, height) = terminal::size()?;
+    let (width, height) = (width as usize, (height as usize).saturating_sub( 1 ));
+    let mut out = io::stdout();
+
+    queue!( out, MoveTo( 0, 0 ), Clear( ClearType::All ) )?;
+
+    let lines = runner.sim().render( width, height );
+
+    for (y, line) in lines.iter().skip( view.pan.1 ).take( height ).enumerate() {
+        let line = line.chars().skip( view.pan.0 ).take( width ).collect::<String>();
+        queue!( out, MoveTo( 0, y as u16 ) )?;
+        write!( out, "{}", line )?;
+    }
+
+    let status = format!(
+        "tick {} | {} | {}ms/tick | {}",
+        runner.tick(),
+        if view.paused { "paused" } else { "running" },
+        view.delay,
+        view.message
+    );
+
+    queue!( out, MoveTo( 0, height as u16 ) )?;
+    write!( out, "{}", status.chars().take( width ).collect::<String>() )?;
+
+    out.flush()
+}
+
+/// Runs `sim` in the terminal until the user quits.
+pub fn run<S: Visualize>( sim: S ) -> Result<(), Box<dyn Error>> {
+    let mut runner = Runner::new( sim );
+    runner.record_last( HISTORY );
+
+    let mut view = View {
+        paused: true,
+        delay: 100,
+        pan: (0, 0),
+        message: "space: run, s/b: step, +/-: speed, n: next event, hjkl: pan, q: quit".into()
+    };
+
+    let _screen = Screen::enter()?;
+
+    loop {
+        draw( &runner, &view )?;
+
+        let timeout = if view.paused { Duration::from_secs( 3600 ) } else { Duration::from_millis( view.delay ) };
+
+        if ! event::poll( timeout )? {
+            // NOTE: A paused view only moves on keys, however long it waits
+            if view.paused {
+                continue;
+            }
+
+            runner.step();
+
+            if let Some( message ) = runner.sim().event() {
+                view.message = message;
+            }
+
+            continue;
+        }
+
+        let key = match event::read()? {
+            Event::Key( key ) if key.kind == KeyEventKind::Press => key.code,
+            _                                                   => continue
+        };
+
+        match key {
+            KeyCode::Char( 'q' ) | KeyCode::Esc         => break,
+            KeyCode::Char( ' ' )                        => view.paused = ! view.paused,
+            KeyCode::Char( '+' ) | KeyCode::Char( '=' ) => view.delay = (view.delay / 2).max( 1 ),
+            KeyCode::Char( '-' )                        => view.delay = (view.delay * 2).min( 5000 ),
+            KeyCode::Char( 'h' )                        => view.pan.0 = view.pan.0.saturating_sub( 10 ),
+            KeyCode::Char( 'l' )                        => view.pan.0 += 10,
+            KeyCode::Char( 'k' )                        => view.pan.1 = view.pan.1.saturating_sub( 5 ),
+            KeyCode::Char( 'j' )                        => view.pan.1 += 5,
+            KeyCode::Char( 's' ) | KeyCode::Right       => {
+                view.paused = true;
+                runner.step();
+                view.message = runner.sim().event().unwrap_or_default();
+            },
+            KeyCode::Char( 'b' ) | KeyCode::Left        => {
+                view.paused = true;
+                runner.rewind( 1 );
+                view.message = runner.sim().event().unwrap_or_default();
+            },
+            KeyCode::Char( 'n' )                        => {
+                view.paused = true;
+                let start = runner.tick();
+
+                runner.step();
+                while runner.sim().event().is_none() && runner.tick() - start < MAX_JUMP {
+                    runner.step();
+                }
+
+                view.message = runner
+                                .sim()
+                                .event()
+                                .unwrap_or_else( || format!( "no event within {} ticks", MAX_JUMP ) );
+            },
+            _                                           => {}
+        }
+    }
+
+    Ok( () )
+}
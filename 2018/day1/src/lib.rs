@@ -0,0 +1,633 @@
+//! Chronal Calibration: frequency changes such as `+1` or `-7`.
+
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, BinaryHeap, HashSet};
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::mem;
+use std::str::FromStr;
+
+use aoc::big::Big;
+use aoc::diff;
+use aoc::gen::Rng;
+
+/// Integers frequencies are added up in, picked with the `precision`
+/// parameter. Nothing ever silently wraps around.
+pub trait Frequency: Clone + Ord + Display + FromStr {
+    /// Name of the precision, as the parameter gives it.
+    const PRECISION: &'static str;
+
+    fn zero() -> Self;
+    fn checked_add( &self, other: &Self ) -> Option<Self>;
+    fn checked_sub( &self, other: &Self ) -> Option<Self>;
+    fn checked_neg( &self ) -> Option<Self>;
+    /// Euclidean quotient and remainder.
+    fn checked_div_rem_euclid( &self, other: &Self ) -> Option<(Self, Self)>;
+    fn to_usize( &self ) -> Option<usize>;
+}
+
+macro_rules! checked_frequency {
+    ($($t:ident),*) => {$(
+        impl Frequency for $t {
+            const PRECISION: &'static str = stringify!( $t );
+
+            fn zero() -> Self { 0 }
+            fn checked_add( &self, other: &Self ) -> Option<Self> { $t::checked_add( *self, *other ) }
+            fn checked_sub( &self, other: &Self ) -> Option<Self> { $t::checked_sub( *self, *other ) }
+            fn checked_neg( &self ) -> Option<Self> { $t::checked_neg( *self ) }
+
+            fn checked_div_rem_euclid( &self, other: &Self ) -> Option<(Self, Self)> {
+                Some( (self.checked_div_euclid( *other )?, self.checked_rem_euclid( *other )?) )
+            }
+
+            fn to_usize( &self ) -> Option<usize> { usize::try_from( *self ).ok() }
+        }
+    )*}
+}
+
+checked_frequency!( i64, i128 );
+
+impl Frequency for Big {
+    const PRECISION: &'static str = "big";
+
+    fn zero() -> Self { Big::default() }
+    fn checked_add( &self, other: &Self ) -> Option<Self> { Some( self.clone() + other.clone() ) }
+    fn checked_sub( &self, other: &Self ) -> Option<Self> { Some( self.clone() - other.clone() ) }
+    fn checked_neg( &self ) -> Option<Self> { Some( -self.clone() ) }
+
+    fn checked_div_rem_euclid( &self, other: &Self ) -> Option<(Self, Self)> {
+        self.div_rem_euclid( other )
+    }
+
+    fn to_usize( &self ) -> Option<usize> {
+        self.to_u128()?.try_into().ok()
+    }
+}
+
+/// Frequency outgrowing the precision it's added up in.
+#[derive(Debug, PartialEq)]
+pub struct Overflow(pub &'static str);
+
+impl Display for Overflow {
+    fn fmt( &self, f: &mut fmt::Formatter ) -> fmt::Result {
+        write!( f, "frequency doesn't fit into {}, pick a wider precision", self.0 )
+    }
+}
+
+impl Error for Overflow {}
+
+/// Frequency changes, one per line.
+pub fn parse<T: Frequency, I: Iterator<Item=String>>( input: I ) -> Result<Vec<T>, String> {
+    input
+        .enumerate()
+        .map( |(i, n)| {
+            n.trim().parse::<T>().map_err( |_| format!( "line {}: '{}' isn't a change which fits into {}", i + 1, n, T::PRECISION ) )
+        } )
+        .collect()
+}
+
+/// Frequency after applying every change once.
+pub fn solve_part1<T: Frequency>( changes: &[T] ) -> Result<T, Overflow> {
+    changes
+        .iter()
+        .try_fold( T::zero(), |sum, change| sum.checked_add( change ) )
+        .ok_or( Overflow( T::PRECISION ) )
+}
+
+/// First frequency reached twice, going over the changes again and again,
+/// or `None` if no frequency ever is.
+// NOTE: Every pass shifts the frequencies of the first one by the drift. Two
+// of them which are `k` drifts apart thus meet `k` passes later, so only
+// frequencies sharing a residue modulo the drift can repeat, each one being
+// caught first by its closest neighbour behind it in the drift's direction.
+pub fn solve_part2<T: Frequency>( changes: &[T] ) -> Result<Option<T>, Overflow> {
+    let overflow = || Overflow( T::PRECISION );
+
+    let mut frequencies = Vec::with_capacity( changes.len() );
+    let mut tree = BTreeSet::default();
+    let mut now = T::zero();
+
+    for change in changes {
+        if ! tree.insert( now.clone() ) { return Ok( Some( now ) ); }
+
+        let next = now.checked_add( change ).ok_or_else( overflow )?;
+        frequencies.push( mem::replace( &mut now, next ) );
+    }
+
+    // NOTE: Covers zero drift too, the start comes back after the first pass
+    if tree.contains( &now ) { return Ok( Some( now ) ); }
+
+    let drift = now;
+    if drift == T::zero() { return Ok( None ); }
+
+    let backwards = drift < T::zero();
+    let modulus = if backwards { drift.checked_neg().ok_or_else( overflow )? } else { drift.clone() };
+
+    // NOTE: Sorted by residue, and then in the drift's direction
+    let mut order = frequencies
+                        .iter()
+                        .enumerate()
+                        .map( |(i, f)| {
+                            let (_, residue) = f.checked_div_rem_euclid( &modulus ).ok_or_else( overflow )?;
+                            let directed = if backwards { f.checked_neg().ok_or_else( overflow )? } else { f.clone() };
+
+                            Ok( (residue, directed, i) )
+                        } )
+                        .collect::<Result<Vec<_>, Overflow>>()?;
+
+    order.sort();
+
+    let first = order
+                    .windows( 2 )
+                    .filter( |w| w[ 0 ].0 == w[ 1 ].0 )
+                    .map( |w| {
+                        let (a, b) = (w[ 0 ].2, w[ 1 ].2);
+                        let gap = frequencies[ b ].checked_sub( &frequencies[ a ] ).ok_or_else( overflow )?;
+                        let (passes, _) = gap.checked_div_rem_euclid( &drift ).ok_or_else( overflow )?;
+
+                        Ok( (passes, a, b) )
+                    } )
+                    .collect::<Result<Vec<_>, Overflow>>()?
+                    .into_iter()
+                    .min();
+
+    Ok( first.map( |(_, _, b)| frequencies[ b ].clone() ) )
+}
+
+/// `solve_part1` straight from the input lines.
+pub fn part1<T: Frequency, I: Iterator<Item=String>>( input: I ) -> Result<T, Box<dyn Error>> {
+    Ok( solve_part1( &parse::<T, _>( input )? ).map_err( |e| e.to_string() )? )
+}
+
+/// `solve_part2` straight from the input lines.
+pub fn part2<T: Frequency, I: Iterator<Item=String>>( input: I ) -> Result<Option<T>, Box<dyn Error>> {
+    Ok( solve_part2( &parse::<T, _>( input )? ).map_err( |e| e.to_string() )? )
+}
+
+fn reference_changes( input: &[String] ) -> Vec<i64> {
+    input.iter().map( |l| l.trim().parse::<i64>().expect( "invalid change" ) ).collect()
+}
+
+/// Reference for part one.
+pub fn reference_part1( input: &[String] ) -> String {
+    reference_changes( input ).iter().sum::<i64>().to_string()
+}
+
+/// Reference: the changes applied over and over with every frequency kept in
+/// a set, for as many passes as it takes to walk across all of them.
+pub fn reference_part2( input: &[String] ) -> String {
+    let changes = reference_changes( input );
+    let spread = changes.iter().map( |c| c.unsigned_abs() as usize ).sum::<usize>();
+
+    let mut seen = HashSet::new();
+    let mut now = 0;
+    seen.insert( now );
+
+    for change in changes.iter().cycle().take( changes.len() * (spread + 2) ) {
+        now += change;
+        if ! seen.insert( now ) { return now.to_string(); }
+    }
+
+    "none".into()
+}
+
+/// Optimised solutions paired with their references, for `diff-test`.
+pub const CHECKS: [diff::Check; 2] = [
+    ("part1", |input| part1::<i64, _>( input.iter().cloned() ).map_or_else( |e| e.to_string(), |f| f.to_string() ), reference_part1),
+    ("part2", |input| part2::<i64, _>( input.iter().cloned() ).map_or_else( |e| e.to_string(), |f| f.map_or( "none".into(), |f| f.to_string() ) ), reference_part2)
+];
+
+/// Frequency reached for the second time, by change `index` (counting from
+/// 0) of pass `pass` (counting from 1).
+#[derive(Debug, PartialEq)]
+pub struct Repeat<T> {
+    pub frequency: T,
+    pub pass: usize,
+    pub index: usize
+}
+
+/// How the frequency changes over time, to sanity-check inputs with.
+#[derive(Debug, PartialEq)]
+pub struct Timeline<T> {
+    /// Frequency after every change of the first pass.
+    pub prefix_sums: Vec<T>,
+    /// Lowest and highest frequency reached, start included, until the last
+    /// repeat was found (or over the first pass, if there's none).
+    pub min: T,
+    pub max: T,
+    pub repeats: Vec<Repeat<T>>,
+    /// Passes through the list it took to find the repeats.
+    pub passes: usize
+}
+
+/// `drift` added to `frequency` `times` times, by doubling.
+fn shifted<T: Frequency>( frequency: &T, drift: &T, times: usize ) -> Option<T> {
+    let (mut sum, mut power, mut times) = (frequency.clone(), drift.clone(), times);
+
+    while times > 0 {
+        if times & 1 == 1 { sum = sum.checked_add( &power )?; }
+
+        times >>= 1;
+        if times > 0 { power = power.checked_add( &power )?; }
+    }
+
+    Some( sum )
+}
+
+/// Lowest and highest of the frequencies and the start.
+fn bounds<T: Frequency>( frequencies: &[T] ) -> (T, T) {
+    frequencies.iter().fold( (T::zero(), T::zero()), |(min, max), f| (min.min( f.clone() ), max.max( f.clone() )) )
+}
+
+/// Pass (counting from 0) in which each change of the first pass starts
+/// landing on frequencies reached before, `None` if it never does.
+// NOTE: As in `solve_part2`, frequencies sharing a residue modulo the drift
+// move along the same lane, one drift every pass. On every step of a lane
+// the change sitting furthest along it in the first pass (the earlier one,
+// if two sit together) gets there first, so a change repeats from the step
+// on where some change ahead of it started. The start sits on the step just
+// behind the last change, which is the drift itself.
+fn first_repeats<T: Frequency>( frequencies: &[T] ) -> Result<Vec<Option<usize>>, Overflow> {
+    let overflow = || Overflow( T::PRECISION );
+    let last = frequencies.len().saturating_sub( 1 );
+    let drift = frequencies.last().cloned().unwrap_or_else( T::zero );
+
+    if drift == T::zero() {
+        let mut seen = BTreeSet::default();
+        seen.insert( T::zero() );
+
+        return Ok( frequencies.iter().map( |f| Some( (seen.insert( f.clone() )) as usize ) ).collect() );
+    }
+
+    let backwards = drift < T::zero();
+    let modulus = if backwards { drift.checked_neg().ok_or_else( overflow )? } else { drift.clone() };
+
+    // NOTE: Sorted by lane, and then from the furthest along
+    let mut lanes = frequencies
+                        .iter()
+                        .enumerate()
+                        .map( |(i, f)| {
+                            let directed = if backwards { f.checked_neg().ok_or_else( overflow )? } else { f.clone() };
+                            let (step, lane) = directed.checked_div_rem_euclid( &modulus ).ok_or_else( overflow )?;
+
+                            Ok( (lane, Reverse( step ), i) )
+                        } )
+                        .collect::<Result<Vec<_>, Overflow>>()?;
+
+    lanes.sort();
+
+    let mut first = vec![ None; frequencies.len() ];
+    for lane in lanes.chunk_by( |a, b| a.0 == b.0 ) {
+        let mut ahead: Option<T> = None;
+
+        for (_, Reverse( step ), i) in lane {
+            if let Some( start ) = &ahead {
+                let wait = start.checked_sub( step ).ok_or_else( overflow )?;
+                first[ *i ] = Some( if wait < T::zero() { 0 } else { wait.to_usize().ok_or( Overflow( "usize" ) )? } );
+            }
+
+            let start = if *i == last { T::zero() } else { step.clone() };
+            ahead = Some( ahead.map_or( start.clone(), |a| a.min( start ) ) );
+        }
+    }
+
+    Ok( first )
+}
+
+/// Timeline of the changes up to their first `count` repeats.
+// NOTE: Once a change starts repeating it does so on every pass after, so
+// the repeats come from merging those runs in order
+pub fn timeline<T: Frequency>( changes: &[T], count: usize ) -> Result<Timeline<T>, Overflow> {
+    let overflow = || Overflow( T::PRECISION );
+    let prefix_sums = changes
+                        .iter()
+                        .scan( T::zero(), |now, change| {
+                            *now = now.checked_add( change )?;
+                            Some( now.clone() )
+                        } )
+                        .collect::<Vec<_>>();
+
+    if prefix_sums.len() < changes.len() {
+        return Err( overflow() );
+    }
+
+    let (min, max) = bounds( &prefix_sums );
+    let drift = prefix_sums.last().cloned().unwrap_or_else( T::zero );
+    let mut runs = first_repeats( &prefix_sums )?
+                    .into_iter()
+                    .enumerate()
+                    .filter_map( |(i, pass)| Some( Reverse( (pass?, i) ) ) )
+                    .collect::<BinaryHeap<_>>();
+
+    let mut repeats = vec![];
+    while repeats.len() < count {
+        let Some( Reverse( (pass, index) ) ) = runs.pop() else { break };
+
+        let frequency = shifted( &prefix_sums[ index ], &drift, pass ).ok_or_else( overflow )?;
+        repeats.push( Repeat { frequency, pass: pass.checked_add( 1 ).ok_or( Overflow( "usize" ) )?, index } );
+        runs.push( Reverse( (pass + 1, index) ) );
+    }
+
+    let mut timeline = Timeline { min, max, prefix_sums, repeats: vec![], passes: (! changes.is_empty()) as usize };
+
+    // NOTE: Frequencies spread out by a drift every pass, so the full passes
+    // reach furthest in the last of them
+    if let Some( &Repeat { pass, index, .. } ) = repeats.last() {
+        let (all_min, all_max) = bounds( &timeline.prefix_sums );
+        let (now_min, now_max) = bounds( &timeline.prefix_sums[ ..= index ] );
+        let mut reached = vec![ shifted( &now_min, &drift, pass - 1 ), shifted( &now_max, &drift, pass - 1 ) ];
+
+        if pass >= 2 {
+            reached.extend( [ shifted( &all_min, &drift, pass - 2 ), shifted( &all_max, &drift, pass - 2 ) ] );
+        }
+
+        for f in reached {
+            let f = f.ok_or_else( overflow )?;
+            timeline.min = timeline.min.min( f.clone() );
+            timeline.max = timeline.max.max( f );
+        }
+
+        timeline.passes = pass;
+    }
+
+    timeline.repeats = repeats;
+    Ok( timeline )
+}
+
+// NOTE: Labels in the first column are aligned to the left, the rest to the right
+fn columns( rows: Vec<Vec<String>> ) -> String {
+    let mut widths = vec![];
+    for row in &rows {
+        widths.resize( widths.len().max( row.len() ), 0 );
+
+        for (width, cell) in widths.iter_mut().zip( row ) {
+            *width = (*width).max( cell.len() );
+        }
+    }
+
+    rows
+        .iter()
+        .map( |row| {
+            row.iter()
+               .zip( &widths )
+               .enumerate()
+               .map( |(i, (cell, &width))| {
+                   if i == 0 { format!( "{:<width$}", cell, width = width ) }
+                   else      { format!( "{:>width$}", cell, width = width ) }
+               } )
+               .collect::<Vec<_>>()
+               .join( "  " )
+        } )
+        .collect::<Vec<_>>()
+        .join( "\n" )
+}
+
+impl<T: Frequency> Timeline<T> {
+    /// Report for people: the timeline, the summary and the repeats.
+    pub fn table( &self, changes: &[T] ) -> String {
+        let mut timeline = vec![ vec![ "index".to_string(), "change".into(), "frequency".into() ] ];
+        for (i, (change, frequency)) in changes.iter().zip( &self.prefix_sums ).enumerate() {
+            timeline.push( vec![ i.to_string(), format!( "{:+}", change ), frequency.to_string() ] );
+        }
+
+        let mut repeats = vec![ vec![ "repeat".to_string(), "pass".into(), "index".into(), "frequency".into() ] ];
+        for (i, repeat) in self.repeats.iter().enumerate() {
+            repeats.push( vec![ (i + 1).to_string(), repeat.pass.to_string(), repeat.index.to_string(), repeat.frequency.to_string() ] );
+        }
+
+        let summary = vec![
+            vec![ "min frequency".to_string(), self.min.to_string() ],
+            vec![ "max frequency".to_string(), self.max.to_string() ],
+            vec![ "passes".to_string(), self.passes.to_string() ]
+        ];
+
+        let repeats = if self.repeats.is_empty() { "no frequency is ever reached twice".into() } else { columns( repeats ) };
+        format!( "{}\n\n{}\n\n{}", columns( timeline ), columns( summary ), repeats )
+    }
+
+    /// Report for spreadsheets, one `kind,pass,index,change,frequency` row
+    /// per step of the first pass, repeat and summary value.
+    pub fn csv( &self, changes: &[T] ) -> String {
+        let mut rows = vec![ "kind,pass,index,change,frequency".to_string() ];
+
+        for (i, (change, frequency)) in changes.iter().zip( &self.prefix_sums ).enumerate() {
+            rows.push( format!( "step,1,{},{:+},{}", i, change, frequency ) );
+        }
+
+        for repeat in &self.repeats {
+            rows.push( format!( "repeat,{},{},{:+},{}", repeat.pass, repeat.index, changes[ repeat.index ], repeat.frequency ) );
+        }
+
+        rows.push( format!( "min,,,,{}", self.min ) );
+        rows.push( format!( "max,,,,{}", self.max ) );
+        rows.push( format!( "passes,{},,,", self.passes ) );
+
+        rows.join( "\n" )
+    }
+}
+
+/// Random changes whose frequencies are bound to repeat.
+// NOTE: With a drift smaller than the number of changes two frequencies of
+// the first pass share a residue modulo the drift, so a repeat is guaranteed.
+pub fn generate( rng: &mut Rng, size: usize ) -> Vec<String> {
+    let size = size.max( 1 );
+    let limit = (size as i64 - 1).min( 20 );
+    let drift = rng.between( -limit, limit );
+
+    let mut changes = (1 .. size)
+                        .map( |_| rng.between( 1, 20 ) * if rng.chance( 0.5 ) { 1 } else { -1 } )
+                        .collect::<Vec<_>>();
+
+    changes.push( drift - changes.iter().sum::<i64>() );
+    changes.iter().map( |c| format!( "{:+}", c ) ).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc::gen::Rng;
+    use proptest::prelude::*;
+    use std::collections::HashSet;
+
+    fn lines( changes: &[i64] ) -> impl Iterator<Item=String> + '_ {
+        changes.iter().map( |c| format!( "{:+}", c ) )
+    }
+
+    // NOTE: A repeat, if there is one, happens before the first pass has
+    // caught up with the whole spread of its frequencies
+    fn cycle_through( changes: &[i64] ) -> Option<i64> {
+        let spread = changes.iter().map( |&c| c.unsigned_abs() as usize ).sum::<usize>();
+        let mut seen = HashSet::new();
+        let mut now = 0;
+        seen.insert( now );
+
+        for change in changes.iter().cycle().take( changes.len() * (spread + 2) ) {
+            now += change;
+            if ! seen.insert( now ) { return Some( now ); }
+        }
+
+        None
+    }
+
+    #[test]
+    fn references_agree() {
+        assert!( diff::check( 50, 300, 0, generate, &CHECKS ).unwrap().is_none() );
+    }
+
+    #[test]
+    fn part2_matches_the_examples() {
+        assert_eq!( solve_part2( &[ 1i64, -1 ] ), Ok( Some( 0 ) ) );
+        assert_eq!( solve_part2( &[ 3i64, 3, 4, -2, -4 ] ), Ok( Some( 10 ) ) );
+        assert_eq!( solve_part2( &[ -6i64, 3, 8, 5, -6 ] ), Ok( Some( 5 ) ) );
+        assert_eq!( solve_part2( &[ 7i64, 7, -2, -7, -4 ] ), Ok( Some( 14 ) ) );
+    }
+
+    #[test]
+    fn part2_tells_when_nothing_repeats() {
+        assert_eq!( solve_part2::<i64>( &[] ), Ok( None ) );
+        assert_eq!( solve_part2( &[ 1i64 ] ), Ok( None ) );
+        assert_eq!( solve_part2( &[ 2i64, 2 ] ), Ok( None ) );
+        assert_eq!( solve_part2( &[ 1i64, 1, 1, 2 ] ), Ok( None ) );
+    }
+
+    #[test]
+    fn part2_does_not_cycle_through_slow_drifts() {
+        assert_eq!( solve_part2( &[ 1_000_000_000i64, -999_999_999 ] ), Ok( Some( 1_000_000_000 ) ) );
+    }
+
+    #[test]
+    fn timeline_finds_later_repeats() {
+        let changes = [ 3i64, 3, 4, -2, -4 ];
+        let timeline = timeline( &changes, 3 ).unwrap();
+
+        assert_eq!( timeline.prefix_sums, vec![ 3, 6, 10, 8, 4 ] );
+        assert_eq!( (timeline.min, timeline.max, timeline.passes), (0, 14, 3) );
+        assert_eq!( timeline.repeats, vec![
+            Repeat { frequency: 10, pass: 2, index: 1 },
+            Repeat { frequency: 8, pass: 2, index: 4 },
+            Repeat { frequency: 14, pass: 3, index: 1 }
+        ] );
+
+        let csv = timeline.csv( &changes );
+        assert!( csv.starts_with( "kind,pass,index,change,frequency\nstep,1,0,+3,3\n" ) );
+        assert!( csv.contains( "\nrepeat,3,1,+3,14\n" ) );
+        assert!( csv.ends_with( "\nmin,,,,0\nmax,,,,14\npasses,3,,," ) );
+        assert!( timeline.table( &changes ).contains( "\n3          3      1         14" ) );
+    }
+
+    #[test]
+    fn timeline_does_not_cycle_through_slow_drifts() {
+        let timeline = timeline( &[ 1_000_000_000i64, -999_999_999 ], 3 ).unwrap();
+
+        assert_eq!( timeline.repeats, vec![
+            Repeat { frequency: 1_000_000_000, pass: 1_000_000_000, index: 1 },
+            Repeat { frequency: 1_000_000_001, pass: 1_000_000_001, index: 1 },
+            Repeat { frequency: 1_000_000_002, pass: 1_000_000_002, index: 1 }
+        ] );
+        assert_eq!( (timeline.min, timeline.max, timeline.passes), (0, 2_000_000_001, 1_000_000_002) );
+    }
+
+    #[test]
+    fn timeline_stops_without_repeats() {
+        let timeline = timeline( &[ 2i64, -3, 5 ], 5 ).unwrap();
+
+        assert_eq!( (timeline.min, timeline.max, timeline.passes), (-1, 4, 1) );
+        assert!( timeline.repeats.is_empty() );
+        assert!( timeline.table( &[ 2, -3, 5 ] ).ends_with( "no frequency is ever reached twice" ) );
+    }
+
+    #[test]
+    fn overflow_is_an_error() {
+        let changes = [ "+9223372036854775807", "+1", "-1" ].iter().map( |s| s.to_string() );
+
+        assert_eq!( solve_part1( &[ i64::MAX, 1 ] ), Err( Overflow( "i64" ) ) );
+        assert_eq!( solve_part2( &[ i64::MAX, 1, -1 ] ), Err( Overflow( "i64" ) ) );
+        assert_eq!( solve_part1( &[ i64::MAX as i128, 1 ] ), Ok( 1 << 63 ) );
+        assert_eq!( part2::<i128, _>( changes.clone() ).unwrap(), Some( i64::MAX as i128 ) );
+        assert_eq!( part2::<Big, _>( changes ).unwrap().map( |f| f.to_string() ), Some( "9223372036854775807".into() ) );
+        assert!( part1::<i64, _>( [ "+9223372036854775808".to_string() ].into_iter() ).is_err() );
+        assert!( part1::<i128, _>( [ "+1".to_string(), "x".to_string() ].into_iter() ).is_err() );
+
+        let huge = "+1".to_string() + &"0".repeat( 50 );
+        assert_eq!( part1::<Big, _>( [ huge.clone(), huge ].into_iter() ).unwrap().to_string(), "2".to_string() + &"0".repeat( 50 ) );
+    }
+
+    // NOTE: Goes pass after pass, the way `timeline` used to
+    fn timeline_by_passes( changes: &[i64], count: usize ) -> (Vec<Repeat<i64>>, i64, i64, usize) {
+        let mut seen = HashSet::new();
+        let (mut now, mut min, mut max) = (0, 0, 0);
+        let mut repeats = vec![];
+        seen.insert( now );
+
+        for f in changes.iter().scan( 0, |now, c| { *now += c; Some( *now ) } ) {
+            (min, max) = (min.min( f ), max.max( f ));
+        }
+
+        for pass in 1 ..= 1000 {
+            for (index, change) in changes.iter().enumerate() {
+                now += change;
+                (min, max) = (min.min( now ), max.max( now ));
+
+                if ! seen.insert( now ) {
+                    repeats.push( Repeat { frequency: now, pass, index } );
+                    if repeats.len() == count { return (repeats, min, max, pass); }
+                }
+            }
+        }
+
+        unreachable!()
+    }
+
+    proptest! {
+        #[test]
+        fn timeline_agrees_with_going_through_passes( changes in prop::collection::vec( -20 .. 20i64, 1 .. 20 ), count in 1 .. 30usize ) {
+            let timeline = timeline( &changes, count ).unwrap();
+
+            if cycle_through( &changes ).is_none() {
+                prop_assert!( timeline.repeats.is_empty() );
+            } else {
+                prop_assert_eq!( (timeline.repeats, timeline.min, timeline.max, timeline.passes), timeline_by_passes( &changes, count ) );
+            }
+        }
+
+        #[test]
+        fn part1_sums_changes( changes in prop::collection::vec( -1000 .. 1000i64, 0 .. 100 ) ) {
+            prop_assert_eq!( part1::<i64, _>( lines( &changes ) ).unwrap(), changes.iter().sum::<i64>() );
+        }
+
+        // NOTE: With zero drift the first repeat is found within a single pass
+        #[test]
+        fn part2_finds_first_repeat_without_drift( mut changes in prop::collection::vec( -1000 .. 1000i64, 1 .. 100 ) ) {
+            let drift = changes.iter().sum::<i64>();
+            changes.push( -drift );
+
+            let mut seen = HashSet::new();
+            let mut now = 0;
+            seen.insert( now );
+
+            for change in &changes {
+                now += change;
+                if ! seen.insert( now ) { break; }
+            }
+
+            prop_assert_eq!( part2::<i64, _>( lines( &changes ) ).unwrap(), Some( now ) );
+        }
+
+        #[test]
+        fn part2_agrees_with_cycling( changes in prop::collection::vec( -20 .. 20i64, 0 .. 30 ) ) {
+            let expected = cycle_through( &changes );
+
+            prop_assert_eq!( solve_part2( &changes ), Ok( expected ) );
+            prop_assert_eq!( part2::<Big, _>( lines( &changes ) ).unwrap().map( |f| f.to_string() ), expected.map( |f| f.to_string() ) );
+        }
+
+        #[test]
+        fn generated_input_is_valid( seed: u64, size in 1 .. 200usize ) {
+            let input = generate( &mut Rng::new( seed ), size );
+
+            prop_assert_eq!( input.len(), size );
+            prop_assert!( part1::<i64, _>( input.iter().cloned() ).is_ok() );
+            prop_assert!( part2::<i64, _>( input.into_iter() ).unwrap().is_some() );
+        }
+    }
+}
@@ -1,49 +1,76 @@
-use std::collections::BTreeSet;
 use std::env;
-use std::fmt::Display;
 use std::fs;
 use std::io::{BufRead, BufReader};
 
-fn part1<I: Iterator<Item=String>>( input: I ) -> impl Display {
-    input
-        .map( |n| n.parse::<i32>().unwrap() )
-        .sum::<i32>()
-}
-
-fn part2<I: Iterator<Item=String>>( input: I ) -> impl Display {
-    let data = input.map( |n| n.parse::<i32>().unwrap() ).collect::<Vec<_>>();
-    let mut now = 0;
-    let mut tree = BTreeSet::default();
-    tree.insert( 0 );
-
-    for num in data.into_iter().cycle() {
-        now += num;
-        if ! tree.insert( now ) { break; }
-    }
+use aoc::big::Big;
+use aoc::config::Config;
+use aoc::diff;
+use aoc::gen;
+use aoc::mem;
+use day1::{generate, parse, part1, part2, solve_part1, solve_part2, timeline, Frequency, CHECKS};
 
-    now
-}
+#[global_allocator]
+static ALLOCATOR: mem::Counting = mem::Counting;
 
 fn usage() -> &'static str {
-    "usage: cargo run --release -- <part1|part2>"
+    "usage: cargo run --release -- <part1|part2> [input] | report [input] [repeats] [table|csv] | gen [size] [seed] | diff-test [cases] [size] [seed] | --profile-mem [input]"
 }
 
-fn main() -> Result<(), Box<std::error::Error>> {
-    let part = env::args().nth( 1 ).unwrap_or_else( || "part1".into() );
-    let first = match part.as_ref() {
-        "part1"     => true,
-        "part2"     => false,
-        _           => return Err( usage().into() )
-    };
+fn run<T: Frequency>( config: &Config, part: &str ) -> Result<(), Box<dyn std::error::Error>> {
+    let input_file = env::args().nth( 2 ).unwrap_or_else( || config.input() );
 
-    let input_file = env::args().nth( 2 ).unwrap_or_else( || "res/input".into() );
+    if part == "--profile-mem" {
+        return mem::profile(
+            &input_file,
+            |input| parse::<T, _>( input ).expect( "invalid input" ),
+            |c| solve_part1( c ).map_or_else( |e| e.to_string(), |f| f.to_string() ),
+            |c| match solve_part2( c ) {
+                Ok( Some( f ) ) => f.to_string(),
+                Ok( None )      => "none".into(),
+                Err( e )        => e.to_string()
+            }
+        );
+    }
 
     let reader = BufReader::new( fs::File::open( input_file )? );
     let mut lines = reader.lines().map( |l| l.expect( "invalid input" ) );
 
-    if first { println!( "{}", part1( &mut lines ) ) }
-    else     { println!( "{}", part2( &mut lines ) ) };
+    if part == "report" {
+        let repeats = env::args().nth( 3 ).map( |n| n.parse::<usize>() ).transpose()?.unwrap_or( 10 );
+        let changes = parse::<T, _>( &mut lines )?;
+        let timeline = timeline( &changes, repeats ).map_err( |e| e.to_string() )?;
 
+        match env::args().nth( 4 ).as_deref() {
+            None | Some( "table" )  => println!( "{}", timeline.table( &changes ) ),
+            Some( "csv" )           => println!( "{}", timeline.csv( &changes ) ),
+            _                       => return Err( usage().into() )
+        }
+
+        return Ok( () );
+    }
+
+    if part == "part1" { config.print( "part1", part1::<T, _>( &mut lines )? ) }
+    else               { config.print( "part2", part2::<T, _>( &mut lines )?.ok_or( "no frequency is ever reached twice" )? ) };
 
     Ok( () )
 }
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let config = Config::load( env!( "CARGO_MANIFEST_DIR" ) )?;
+    config.watch();
+
+    let part = env::args().nth( 1 ).unwrap_or_else( || config.part() );
+    match part.as_ref() {
+        "part1" | "part2" | "report" | "--profile-mem" => {},
+        "gen"                                           => return gen::run( 1000, generate ),
+        "diff-test"                                     => return diff::run( 1000, generate, &CHECKS ),
+        _                                               => return Err( usage().into() )
+    }
+
+    match config.param( "precision", "i64".to_string() )?.as_ref() {
+        "i64"   => run::<i64>( &config, &part ),
+        "i128"  => run::<i128>( &config, &part ),
+        "big"   => run::<Big>( &config, &part ),
+        other   => Err( format!( "unknown precision '{}', pick i64, i128 or big", other ).into() )
+    }
+}
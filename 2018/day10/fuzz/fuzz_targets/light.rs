@@ -0,0 +1,13 @@
+#![no_main]
+
+use day10::parse_light;
+use libfuzzer_sys::fuzz_target;
+
+// Malformed lights have to be rejected with an error, never with a panic.
+fuzz_target!(|data: &[u8]| {
+    if let Ok( input ) = std::str::from_utf8( data ) {
+        for line in input.lines() {
+            let _ = parse_light( line );
+        }
+    }
+});
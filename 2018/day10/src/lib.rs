@@ -0,0 +1,376 @@
+//! Moving lights, e.g. `position=< 9,  1> velocity=< 0,  2>`, and the message they spell.
+
+use std::collections::HashSet;
+use std::str::FromStr;
+
+use aoc::export::{self, Animation, Image};
+use aoc::gen::Rng;
+use aoc::sim::{Runner, Simulation};
+use aoc::viz::Visualize;
+use nom::*;
+
+pub type Scalar = i64;
+pub type Vector = (Scalar, Scalar);
+
+/// Light with its position and the distance it moves every second.
+#[derive(Debug, Clone)]
+pub struct Light {
+    pub position: Vector,
+    pub velocity: Vector
+}
+
+impl Light {
+    /// Moves the light by a single second.
+    pub fn update( &mut self ) {
+        self.position.0 += self.velocity.0;
+        self.position.1 += self.velocity.1;
+    }
+}
+
+named!(pub number(&str) -> i64,
+    map_res!(
+        recognize!(
+            pair!(
+                opt!( char!( '-' ) ),
+                digit
+            )
+        ),
+        i64::from_str
+    )
+);
+
+named!(pub parse_tuple(&str) -> Vector,
+    do_parse!(
+        char!( '<' )    >>
+        opt!( space )   >>
+        fst: number     >>
+        char!( ',' )    >>
+        space           >>
+        snd: number     >>
+        char!( '>' )    >>
+        ((fst, snd))
+    )
+);
+
+named!(pub parse_light(&str) -> Light,
+    do_parse!(
+        tag!( "position=" )     >>
+        position: parse_tuple   >>
+        space                   >>
+        tag!( "velocity=" )     >>
+        velocity: parse_tuple   >>
+        (Light {
+            position,
+            velocity
+        })
+    )
+);
+
+/// All of the lights, moving a step every second.
+pub struct Sky {
+    pub lights: Vec<Light>
+}
+
+impl Sky {
+    /// Where the lights are right now.
+    pub fn positions( &self ) -> HashSet<Vector> {
+        self.lights.iter().map( |l| l.position ).collect()
+    }
+
+    // NOTE: Assumption: _text_ is assembled from not separated points.
+    /// Whether the lights spell a message.
+    pub fn is_text( &self ) -> bool {
+        let positions = self.positions();
+
+        positions
+            .iter()
+            .all( |position| ! separated( position, &positions ) )
+    }
+}
+
+impl Simulation for Sky {
+    type Snapshot = Vec<Vector>;
+
+    fn step( &mut self ) {
+        for light in &mut self.lights {
+            light.update();
+        }
+    }
+
+    fn snapshot( &self ) -> Self::Snapshot {
+        self.lights.iter().map( |l| l.position ).collect()
+    }
+
+    fn restore( &mut self, snapshot: &Self::Snapshot ) {
+        for (light, &position) in self.lights.iter_mut().zip( snapshot ) {
+            light.position = position;
+        }
+    }
+}
+
+impl Visualize for Sky {
+    // NOTE: Lights start far apart, so they are squeezed to fit the view
+    fn render( &self, width: usize, height: usize ) -> Vec<String> {
+        let (mut min_x, mut min_y) = (i64::MAX, i64::MAX);
+        let (mut max_x, mut max_y) = (i64::MIN, i64::MIN);
+
+        for light in &self.lights {
+            min_x = min_x.min( light.position.0 );
+            min_y = min_y.min( light.position.1 );
+            max_x = max_x.max( light.position.0 );
+            max_y = max_y.max( light.position.1 );
+        }
+
+        let scale = i64::max(
+            (max_x - min_x) / width.max( 1 ) as i64,
+            (max_y - min_y) / height.max( 1 ) as i64
+        ) + 1;
+
+        let rows = ((max_y - min_y) / scale + 1) as usize;
+        let cols = ((max_x - min_x) / scale + 1) as usize;
+        let mut grid = vec![ vec![ ' '; cols ]; rows ];
+
+        for light in &self.lights {
+            let (x, y) = ((light.position.0 - min_x) / scale, (light.position.1 - min_y) / scale);
+            grid[ y as usize ][ x as usize ] = '#';
+        }
+
+        grid.into_iter().map( |l| l.into_iter().collect() ).collect()
+    }
+
+    fn event( &self ) -> Option<String> {
+        if self.is_text() {
+            Some( "lights form a text".into() )
+        } else {
+            None
+        }
+    }
+}
+
+/// Whether no other light touches `position`, diagonals included.
+pub fn separated( position: &Vector, positions: &HashSet<Vector> ) -> bool {
+    for dy in -1 ..= 1 {
+        for dx in -1 ..= 1 {
+            if dy == 0 && dx == 0 { continue; }
+            if positions.contains( &(position.0 + dx, position.1 + dy) ) {
+                return false;
+            }
+        }
+    }
+
+    true
+}
+
+/// Prints the points to stdout, `#` for a light.
+pub fn draw_text( points: & HashSet<Vector> ) {
+    let (mut min_x, mut min_y) = (i64::MAX, i64::MAX);
+    let (mut max_x, mut max_y) = (i64::MIN, i64::MIN);
+
+    for point in points {
+        min_x = min_x.min( point.0 );
+        min_y = min_y.min( point.1 );
+        max_x = max_x.max( point.0 );
+        max_y = max_y.max( point.1 );
+    }
+
+    for y in min_y ..= max_y {
+        for x in min_x ..= max_x {
+            if points.contains( &(x, y) ) {
+                print!( "#" );
+            } else {
+                print!( " " );
+            }
+        }
+
+        println!();
+    }
+}
+
+/// One light per line.
+pub fn parse<I: Iterator<Item=String>>( input: I ) -> Result<Vec<Light>, String> {
+    input
+        .map( |i| parse_light( &i ).map( |(_, light)| light ).map_err( |_| format!( "invalid light: '{}'", i ) ) )
+        .collect()
+}
+
+/// Seconds until the lights spell a message, which is printed as well.
+// NOTE: Assumption: first occurrence of _text_ is the correct one.
+pub fn solve_part1( lights: &[Light] ) -> usize {
+    let mut runner = Runner::new( Sky { lights: lights.to_vec() } );
+    let seconds = runner.run_until( Sky::is_text );
+
+    draw_text( &runner.sim().positions() );
+    seconds
+}
+
+/// Same as part one, the message is the first answer and the time the second.
+pub fn solve_part2( lights: &[Light] ) -> usize {
+    solve_part1( lights )
+}
+
+/// `solve_part1` straight from the input lines.
+pub fn part1<I: Iterator<Item=String>>( input: I ) -> Result<usize, String> {
+    Ok( solve_part1( &parse( input )? ) )
+}
+
+/// `solve_part2` straight from the input lines.
+pub fn part2<I: Iterator<Item=String>>( input: I ) -> Result<usize, String> {
+    Ok( solve_part2( &parse( input )? ) )
+}
+
+/// Animates a couple of seconds around the moment the text shows up.
+pub fn export<I: Iterator<Item=String>>( input: I, output: &str ) -> Result<(), Box<dyn std::error::Error>> {
+    const AROUND: usize = 8;
+
+    let lights = parse( input )?;
+    let seconds = Runner::new( Sky { lights: lights.clone() } ).run_until( Sky::is_text );
+
+    let mut runner = Runner::new( Sky { lights } );
+    runner.step_n( seconds.saturating_sub( AROUND ) );
+    runner.record();
+    runner.step_n( seconds + AROUND - runner.tick() );
+
+    let (mut min_x, mut min_y) = (i64::MAX, i64::MAX);
+    let (mut max_x, mut max_y) = (i64::MIN, i64::MIN);
+
+    for (_, positions) in runner.trace() {
+        for point in positions {
+            min_x = min_x.min( point.0 );
+            min_y = min_y.min( point.1 );
+            max_x = max_x.max( point.0 );
+            max_y = max_y.max( point.1 );
+        }
+    }
+
+    let (width, height) = ((max_x - min_x + 1) as usize, (max_y - min_y + 1) as usize);
+    let mut animation = Animation::new( 20 );
+
+    for (tick, positions) in runner.trace() {
+        let mut frame = Image::new( width, height );
+
+        for point in positions {
+            frame.set_signed( point.0 - min_x, point.1 - min_y, export::WHITE );
+        }
+
+        let delay = if tick == seconds { 200 } else { 20 };
+        animation.push_delayed( frame.scaled( 4 ), delay );
+    }
+
+    animation.save( output )
+}
+
+/// Grows a blob where every light touches another one, then sends each light
+/// back along its velocity for a couple thousand seconds.
+pub fn generate( rng: &mut Rng, size: usize ) -> Vec<String> {
+    let seconds = rng.between( 8000, 12000 );
+    let mut blob = vec![ (0, 0) ];
+    let mut taken = HashSet::new();
+    taken.insert( (0, 0) );
+
+    // NOTE: A single light is never part of a text
+    while blob.len() < size.max( 2 ) {
+        let &(x, y) = rng.pick( &blob );
+        let next = (x + rng.between( -1, 1 ), y + rng.between( -1, 1 ));
+
+        if taken.insert( next ) {
+            blob.push( next );
+        }
+    }
+
+    blob.into_iter()
+        .map( |(x, y)| {
+            let velocity = (rng.between( -5, 5 ), rng.between( -5, 5 ));
+            let position = (x - velocity.0 * seconds, y - velocity.1 * seconds);
+
+            format!(
+                "position=<{:>7}, {:>7}> velocity=<{:>2}, {:>2}>",
+                position.0, position.1, velocity.0, velocity.1
+            )
+        } )
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc::gen::Rng;
+    use proptest::prelude::*;
+
+    fn vector() -> impl Strategy<Value=Vector> {
+        (-100_000 .. 100_000i64, -100_000 .. 100_000i64)
+    }
+
+    proptest! {
+        #[test]
+        fn parse_light_round_trips( position in vector(), velocity in vector() ) {
+            let line = format!(
+                "position=<{:>7}, {:>7}> velocity=<{:>2}, {:>2}>",
+                position.0, position.1, velocity.0, velocity.1
+            );
+
+            let light = parse_light( &line ).unwrap().1;
+
+            prop_assert_eq!( light.position, position );
+            prop_assert_eq!( light.velocity, velocity );
+        }
+
+        #[test]
+        fn parse_light_rejects_garbage( position in vector(), junk in "[a-z]{1,3}" ) {
+            let malformed = [
+                format!( "position=<{}, {}> velocity=<{}, 1>", position.0, position.1, junk ),
+                format!( "position=<{}, {}>", position.0, position.1 ),
+                format!( "position={}, {} velocity=<1, 1>", position.0, position.1 ),
+                format!( "{}=<{}, {}> velocity=<1, 1>", junk, position.0, position.1 )
+            ];
+
+            for line in &malformed {
+                prop_assert!( parse_light( line ).is_err(), "accepted '{}'", line );
+                prop_assert!( parse( std::iter::once( line.clone() ) ).is_err() );
+            }
+        }
+
+        #[test]
+        fn rewinding_the_sky_restores_positions(
+            specs in prop::collection::vec( (vector(), vector()), 1 .. 20 ),
+            ticks in 0 .. 50usize,
+            back in 0 .. 50usize
+        ) {
+            let lights = specs
+                            .iter()
+                            .map( |&(position, velocity)| Light { position, velocity } )
+                            .collect::<Vec<_>>();
+
+            let mut runner = Runner::new( Sky { lights } );
+            runner.record();
+            runner.step_n( ticks );
+
+            let back = back.min( ticks );
+            prop_assert!( runner.rewind( back ) );
+
+            let elapsed = (ticks - back) as i64;
+            let expected = specs
+                            .iter()
+                            .map( |&(p, v)| (p.0 + v.0 * elapsed, p.1 + v.1 * elapsed) )
+                            .collect::<Vec<_>>();
+
+            prop_assert_eq!( runner.sim().snapshot(), expected );
+        }
+    }
+
+    // NOTE: Every case simulates thousands of seconds, so there are fewer of them
+    proptest! {
+        #![proptest_config( ProptestConfig::with_cases( 16 ) )]
+        #[test]
+        fn generated_lights_form_a_text( seed: u64, size in 2 .. 50usize ) {
+            let input = generate( &mut Rng::new( seed ), size );
+            let lights = parse( input.into_iter() ).unwrap();
+
+            let mut runner = Runner::new( Sky { lights } );
+            while ! runner.sim().is_text() && runner.tick() < 12000 {
+                runner.step();
+            }
+
+            prop_assert!( runner.sim().is_text() );
+        }
+    }
+}
@@ -1,158 +1,50 @@
-use std::collections::HashSet;
 use std::env;
-use std::fmt::Display;
 use std::fs;
-use std::i64;
 use std::io::{BufRead, BufReader};
-use std::str::FromStr;
 
-use nom::*;
+use aoc::config::Config;
+use aoc::gen;
+use aoc::mem;
+use aoc::viz;
+use day10::{export, generate, parse, part1, part2, solve_part1, solve_part2, Sky};
 
-type Scalar = i64;
-type Vector = (Scalar, Scalar);
+#[global_allocator]
+static ALLOCATOR: mem::Counting = mem::Counting;
 
-#[derive(Debug)]
-struct Light {
-    position: Vector,
-    velocity: Vector
-}
-
-impl Light {
-    fn update( &mut self ) {
-        self.position.0 += self.velocity.0;
-        self.position.1 += self.velocity.1;
-    }
+fn usage() -> &'static str {
+    "usage: cargo run --release -- <part1|part2|export|viz> [input] [output] | gen [size] [seed] | --profile-mem [input]"
 }
 
-named!(number(&str) -> i64,
-    map_res!(
-        recognize!(
-            pair!(
-                opt!( char!( '-' ) ),
-                digit
-            )
-        ),
-        i64::from_str
-    )
-);
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let config = Config::load( env!( "CARGO_MANIFEST_DIR" ) )?;
+    config.watch();
 
-named!(parse_tuple(&str) -> Vector,
-    do_parse!(
-        char!( '<' )    >>
-        opt!( space )   >>
-        fst: number     >>
-        char!( ',' )    >>
-        space           >>
-        snd: number     >>
-        char!( '>' )    >>
-        ((fst, snd))
-    )
-);
-
-named!(parse_light(&str) -> Light,
-    do_parse!(
-        tag!( "position=" )     >>
-        position: parse_tuple   >>
-        space                   >>
-        tag!( "velocity=" )     >>
-        velocity: parse_tuple   >>
-        (Light {
-            position,
-            velocity
-        })
-    )
-);
-
-fn separated( position: &Vector, positions: &HashSet<Vector> ) -> bool {
-    for dy in -1 ..= 1 {
-        for dx in -1 ..= 1 {
-            if dy == 0 && dx == 0 { continue; }
-            if positions.contains( &(position.0 + dx, position.1 + dy) ) {
-                return false;
-            }
-        }
+    let part = env::args().nth( 1 ).unwrap_or_else( || config.part() );
+    if part == "--profile-mem" {
+        let input_file = env::args().nth( 2 ).unwrap_or_else( || config.input() );
+        return mem::profile( &input_file, |input| parse( input ).expect( "invalid input" ), |l| solve_part1( l ), |l| solve_part2( l ) );
     }
 
-    true
-}
-
-fn draw_text( points: & HashSet<Vector> ) {
-    let (mut min_x, mut min_y) = (i64::MAX, i64::MAX);
-    let (mut max_x, mut max_y) = (i64::MIN, i64::MIN);
-
-    for point in points {
-        min_x = min_x.min( point.0 );
-        min_y = min_y.min( point.1 );
-        max_x = max_x.max( point.0 );
-        max_y = max_y.max( point.1 );
+    if part == "gen" {
+        return gen::run( 350, generate );
     }
 
-    for y in min_y ..= max_y {
-        for x in min_x ..= max_x {
-            if points.contains( &(x, y) ) {
-                print!( "#" );
-            } else {
-                print!( " " );
-            }
-        }
-
-        println!( "" );
+    if ! ["part1", "part2", "export", "viz"].contains( &part.as_ref() ) {
+        return Err( usage().into() );
     }
-}
-
-// NOTE: Assumption: _text_ is assembled from not separated points.
-// NOTE: Assumption 2: first occurrence of so defined _text_ is the correct one.
-fn part1<I: Iterator<Item=String>>( input: I ) -> impl Display {
-    let mut lights = input.map( |i| parse_light( &i ).unwrap().1 ).collect::<Vec<_>>();
-    let mut positions = HashSet::with_capacity( lights.len() );
-
-    let mut seconds = 0;
-
-    'outer: loop {
-        seconds += 1;
-        positions.clear();
 
-        for light in &mut lights {
-            light.update();
-            positions.insert( light.position );
-        }
-
-        for position in &positions {
-            if separated( position, &positions ) {
-                continue 'outer;
-            }
-        }
-
-        break;
-    }
-
-    draw_text( &positions );
-    seconds
-}
-
-fn part2<I: Iterator<Item=String>>( input: I ) -> impl Display {
-    part1( input )
-}
-
-fn usage() -> &'static str {
-    "usage: cargo run --release -- <part1|part2>"
-}
-
-fn main() -> Result<(), Box<std::error::Error>> {
-    let part = env::args().nth( 1 ).unwrap_or_else( || "part1".into() );
-    let first = match part.as_ref() {
-        "part1"     => true,
-        "part2"     => false,
-        _           => return Err( usage().into() )
-    };
-
-    let input_file = env::args().nth( 2 ).unwrap_or_else( || "res/input".into() );
+    let input_file = env::args().nth( 2 ).unwrap_or_else( || config.input() );
+    let output_file = env::args().nth( 3 ).unwrap_or_else( || "export.gif".into() );
 
     let reader = BufReader::new( fs::File::open( input_file )? );
     let mut lines = reader.lines().map( |l| l.expect( "invalid input" ) );
 
-    if first { println!( "{}", part1( &mut lines ) ) }
-    else     { println!( "{}", part2( &mut lines ) ) };
+    match part.as_ref() {
+        "part1"  => config.print( "part1", part1( &mut lines )? ),
+        "part2"  => config.print( "part2", part2( &mut lines )? ),
+        "export" => export( &mut lines, &output_file )?,
+        _        => viz::run( Sky { lights: parse( lines )? } )?
+    }
 
 
     Ok( () )
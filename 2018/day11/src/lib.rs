@@ -0,0 +1,315 @@
+//! Chronal Charge: fuel cell grids given by a serial number such as `18`.
+
+use aoc::diff;
+use aoc::export::{self, Image};
+use aoc::gen::Rng;
+use aoc::repl::{self, arg};
+
+/// Width and height of the puzzle grid.
+pub const SIZE: usize = 300;
+
+/// Power levels of a square grid of fuel cells, summed for quick lookups.
+pub struct Grid(Vec<Vec<i64>>);
+
+// Dynamic Programming approach
+//
+// DP[y][x] = DP[y - 1][x] + sum i=0..x A[y][i] | if 0 <= y - 1 < HEIGHT
+//            sum i=0..x A[y][i]                | otherwise
+//
+// Then, sum of sub square at (x,y) of size (s) can be obtained in O(1) by
+// DP[y + s - 1][x + s - 1] - DP[y - 1][x + s - 1] - DP[y + s - 1][x - 1] + DP[y - 1][x - 1]
+// where DP[y][x] = 0 if x, y are out of bounds
+
+impl Grid {
+    /// Power level of the cell at `(x, y)`, counting from 1.
+    pub fn power_level( x: i64, y: i64, serial: i64 ) -> i64 {
+        let rack = x + 10;
+        let mut power = rack * y;
+
+        power += serial;
+        power *= rack;
+        power = ( power / 100 ) % 10;
+        power -= 5;
+
+        power
+    }
+
+    /// Grid with `size` cells on each side.
+    // NOTE: The puzzle grid is always SIZE cells wide, smaller ones keep
+    // reference solutions fast
+    pub fn from_serial( serial: i64, size: usize ) -> Self {
+        let mut grid = vec![vec![0; size]; size];
+
+        for y in 0 .. size {
+            let mut x_value = 0;
+            let above = if y > 0 { grid[ y - 1 ].clone() } else { vec![0; size] };
+
+            for (x, cell) in grid[ y ].iter_mut().enumerate() {
+                x_value += Self::power_level( x as i64 + 1, y as i64 + 1, serial );
+
+                *cell = x_value + above[ x ];
+            }
+        }
+
+        Grid( grid )
+    }
+
+    /// Number of cells on each side.
+    pub fn size( &self ) -> usize {
+        self.0.len()
+    }
+
+    /// Total power of the square with its top left cell at `(x, y)` (counting
+    /// from 0) and `offset + 1` cells on each side.
+    pub fn get_square_power( &self, x: usize, y: usize, offset: usize ) -> i64 {
+        assert!( x + offset < self.size() );
+        assert!( y + offset < self.size() );
+
+        let dp = |y: Option<usize>, x: Option<usize>| match (y, x) {
+            (Some( y ), Some( x ))  => (self.0)[ y ][ x ],
+            _                       => 0
+        };
+
+        let (top, left) = (y.checked_sub( 1 ), x.checked_sub( 1 ));
+        let (bottom, right) = (Some( y + offset ), Some( x + offset ));
+
+        dp( bottom, right ) - dp( top, right ) - dp( bottom, left ) + dp( top, left )
+    }
+}
+
+/// `x,y` of the 3x3 square with the most power.
+pub fn largest_3x3( grid: &Grid ) -> String {
+    let last = grid.size() - 2;
+
+    (0 .. last)
+        .flat_map( |y| (0 .. last).map( move |x| (y, x) ) )
+        .map( |(y, x)| (grid.get_square_power( x, y, 2 ), (y, x)) )
+        .max()
+        .map( |(_, (y, x))| format!( "{},{}", x + 1, y + 1 ) )
+        .unwrap()
+}
+
+/// `x,y,size` of the square of any size with the most power.
+pub fn largest_square( grid: &Grid ) -> String {
+    let size = grid.size();
+    let mut max = i64::MIN;
+    let mut answer = (size + 1, size + 1, size + 1);
+
+    for offset in 0 .. size {
+        for y in 0 .. size - offset {
+            for x in 0 .. size - offset {
+                let power = grid.get_square_power( x, y, offset );
+                if power > max {
+                    max = power;
+                    answer = (x + 1, y + 1, offset + 1);
+                }
+            }
+        }
+    }
+
+    format!( "{},{},{}", answer.0, answer.1, answer.2 )
+}
+
+/// Grid serial number, on the first line.
+pub fn parse<I: Iterator<Item=String>>( input: I ) -> i64 {
+    input
+        .into_iter()
+        .next()
+        .and_then( |l| l.parse::<i64>().ok() )
+        .unwrap()
+}
+
+/// Top left cell of the 3x3 square with the most power.
+pub fn solve_part1( &serial: &i64 ) -> String {
+    largest_3x3( &Grid::from_serial( serial, SIZE ) )
+}
+
+/// Top left cell and size of the square with the most power.
+pub fn solve_part2( &serial: &i64 ) -> String {
+    largest_square( &Grid::from_serial( serial, SIZE ) )
+}
+
+/// `solve_part1` straight from the input lines.
+pub fn part1<I: Iterator<Item=String>>( input: I ) -> String {
+    solve_part1( &parse( input ) )
+}
+
+/// `solve_part2` straight from the input lines.
+pub fn part2<I: Iterator<Item=String>>( input: I ) -> String {
+    solve_part2( &parse( input ) )
+}
+
+/// Reference: adds up every cell of every square, ties go the same way as in
+/// the solutions above.
+pub fn slow_square_power( serial: i64, x: usize, y: usize, side: usize ) -> i64 {
+    (y .. y + side)
+        .flat_map( |y| (x .. x + side).map( move |x| (x, y) ) )
+        .map( |(x, y)| Grid::power_level( x as i64 + 1, y as i64 + 1, serial ) )
+        .sum()
+}
+
+/// Reference for `largest_3x3` on a grid with `size` cells on each side.
+pub fn reference_3x3( serial: i64, size: usize ) -> String {
+    (0 .. size - 2)
+        .flat_map( |y| (0 .. size - 2).map( move |x| (y, x) ) )
+        .max_by_key( |&(y, x)| (slow_square_power( serial, x, y, 3 ), (y, x)) )
+        .map( |(y, x)| format!( "{},{}", x + 1, y + 1 ) )
+        .unwrap()
+}
+
+/// Reference for `largest_square` on a grid with `size` cells on each side.
+pub fn reference_square( serial: i64, size: usize ) -> String {
+    let mut best = None;
+
+    for side in 1 ..= size {
+        for y in 0 ..= size - side {
+            for x in 0 ..= size - side {
+                let power = slow_square_power( serial, x, y, side );
+                if best.is_none_or( |(max, _)| power > max ) {
+                    best = Some( (power, (x + 1, y + 1, side)) );
+                }
+            }
+        }
+    }
+
+    let (_, (x, y, side)) = best.unwrap();
+    format!( "{},{},{}", x, y, side )
+}
+
+// NOTE: Summing every square of the whole grid takes ages, so part two is
+// compared on a smaller one
+const DIFF_SIZE: usize = 24;
+
+fn serial( input: &[String] ) -> i64 {
+    input[ 0 ].parse().unwrap()
+}
+
+/// Optimised solutions paired with their references, for `diff-test`.
+pub const CHECKS: [diff::Check; 2] = [
+    ("part1", |input| part1( input.iter().cloned() ).to_string(), |input| reference_3x3( serial( input ), SIZE )),
+    ("part2", |input| largest_square( &Grid::from_serial( serial( input ), DIFF_SIZE ) ), |input| reference_square( serial( input ), DIFF_SIZE ))
+];
+
+// Top left corner and side of a square given as puzzle coordinates, checked
+// against the grid.
+fn square( grid: &Grid, args: &[&str] ) -> Result<(usize, usize, usize), String> {
+    let (x, y, side) = (arg::<usize>( args, 0, "x" )?, arg::<usize>( args, 1, "y" )?, arg::<usize>( args, 2, "size" )?);
+
+    if x == 0 || y == 0 || side == 0 || x.max( y ) + side - 1 > grid.size() {
+        return Err( format!( "the square doesn't fit the {0}x{0} grid", grid.size() ) );
+    }
+
+    Ok( (x - 1, y - 1, side) )
+}
+
+/// Commands of `repl`, over the grid of the puzzle input.
+pub const COMMANDS: [repl::Command<Grid>; 2] = [
+    repl::Command {
+        name: "power",
+        args: "<x> <y> <size>",
+        help: "total power of the square with its top left cell at x,y",
+        run: |grid, args| {
+            let (x, y, side) = square( grid, args )?;
+            Ok( grid.get_square_power( x, y, side - 1 ).to_string() )
+        }
+    },
+    repl::Command {
+        name: "best",
+        args: "<size>",
+        help: "x,y and power of the square of that size with the most power",
+        run: |grid, args| {
+            let side = arg::<usize>( args, 0, "size" )?;
+            if side == 0 || side > grid.size() {
+                return Err( format!( "size has to be between 1 and {}", grid.size() ) );
+            }
+
+            let last = grid.size() - side + 1;
+            let (power, (y, x)) = (0 .. last)
+                                    .flat_map( |y| (0 .. last).map( move |x| (y, x) ) )
+                                    .map( |(y, x)| (grid.get_square_power( x, y, side - 1 ), (y, x)) )
+                                    .max()
+                                    .unwrap();
+
+            Ok( format!( "{},{} with {}", x + 1, y + 1, power ) )
+        }
+    }
+];
+
+/// Saves the power levels of the grid as a PNG image.
+pub fn export<I: Iterator<Item=String>>( input: I, output: &str ) -> Result<(), Box<dyn std::error::Error>> {
+    let serial = input
+                    .into_iter()
+                    .next()
+                    .and_then( |l| l.parse::<i64>().ok() )
+                    .ok_or( "invalid serial number" )?;
+
+    // NOTE: Power levels are always within -5 ..= 4
+    let image = Image::from_fn( SIZE, SIZE, |x, y| {
+        let power = Grid::power_level( x as i64 + 1, y as i64 + 1, serial );
+        export::gradient( [0x20, 0x30, 0xc0], export::RED, (power + 5) as f64 / 9.0 )
+    } );
+
+    image.scaled( 2 ).save( output )
+}
+
+/// The grid is always SIZE cells wide, so the size only bounds the serial number.
+pub fn generate( rng: &mut Rng, size: usize ) -> Vec<String> {
+    vec![ rng.between( 1, size.max( 1 ) as i64 ).to_string() ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn power_level_examples() {
+        assert_eq!( Grid::power_level( 3, 5, 8 ), 4 );
+        assert_eq!( Grid::power_level( 122, 79, 57 ), -5 );
+        assert_eq!( Grid::power_level( 217, 196, 39 ), 0 );
+        assert_eq!( Grid::power_level( 101, 153, 71 ), 4 );
+    }
+
+    #[test]
+    fn repl_matches_the_examples() {
+        let grid = Grid::from_serial( 18, SIZE );
+
+        assert_eq!( repl::eval( &grid, &COMMANDS, "power 33 45 3" ), Ok( "29".into() ) );
+        assert_eq!( repl::eval( &grid, &COMMANDS, "best 16" ), Ok( "90,269 with 113".into() ) );
+        assert!( repl::eval( &grid, &COMMANDS, "power 299 1 3" ).is_err() );
+    }
+
+    #[test]
+    fn references_agree() {
+        assert!( diff::check( 4, 10_000, 0, generate, &CHECKS ).unwrap().is_none() );
+    }
+
+    proptest! {
+        #![proptest_config( ProptestConfig::with_cases( 32 ) )]
+
+        #[test]
+        fn square_power_matches_brute_force(
+            serial in 0 .. 10_000i64,
+            (offset, x, y) in (0 .. 300usize).prop_flat_map( |o| (Just( o ), 0 .. 300 - o, 0 .. 300 - o) )
+        ) {
+            let grid = Grid::from_serial( serial, SIZE );
+            let expected = (y ..= y + offset)
+                                .flat_map( |y| (x ..= x + offset).map( move |x| (x, y) ) )
+                                .map( |(x, y)| Grid::power_level( x as i64 + 1, y as i64 + 1, serial ) )
+                                .sum::<i64>();
+
+            prop_assert_eq!( grid.get_square_power( x, y, offset ), expected );
+        }
+
+        #[test]
+        fn corner_squares_do_not_underflow( serial in 0 .. 10_000i64, offset in 0 .. 300usize ) {
+            let grid = Grid::from_serial( serial, SIZE );
+            let expected = (0 ..= offset)
+                                .flat_map( |y| (0 ..= offset).map( move |x| (x, y) ) )
+                                .map( |(x, y)| Grid::power_level( x as i64 + 1, y as i64 + 1, serial ) )
+                                .sum::<i64>();
+
+            prop_assert_eq!( grid.get_square_power( 0, 0, offset ), expected );
+        }
+    }
+}
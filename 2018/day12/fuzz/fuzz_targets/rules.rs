@@ -0,0 +1,19 @@
+#![no_main]
+
+use day12::{parse_input, parse_rule};
+use libfuzzer_sys::fuzz_target;
+
+// Malformed input, from a missing blank line after the header to a broken
+// rule, has to be rejected with an error, never with a panic. Every rule that
+// parses has to fit into `Rules`.
+fuzz_target!(|data: &[u8]| {
+    if let Ok( input ) = std::str::from_utf8( data ) {
+        let _ = parse_input( input.lines().map( String::from ) );
+
+        for line in input.lines() {
+            if let Ok( (_, (rule, _)) ) = parse_rule( line ) {
+                assert!( rule < 32 );
+            }
+        }
+    }
+});
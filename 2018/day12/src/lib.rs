@@ -0,0 +1,447 @@
+//! Rows of plant pots, e.g. `initial state: #..#.#` with rules such as `..#.. => #`.
+
+use std::collections::{HashMap, HashSet};
+
+use aoc::diff;
+use aoc::export::{self, Image};
+use aoc::gen::Rng;
+use aoc::info;
+use aoc::inputs;
+use aoc::sim::{Runner, Simulation};
+use aoc::viz::Visualize;
+use bitvec::prelude::*;
+use nom::*;
+
+/// Whether a pot has a plant in the next generation, indexed by the pattern
+/// of the five pots around it (see `to_bits`).
+pub type Rules = [bool; 32];
+
+/// Pots as bits, set for the ones with a plant.
+pub fn to_bitvec( input: &str ) -> BitVec {
+    input
+        .chars()
+        .fold( BitVec::default(), |mut a, c| {
+            a.push( c == '#' );
+            a
+        } )
+}
+
+/// Pattern of pots as a number, the leftmost pot being the highest bit.
+pub fn to_bits( input: &str ) -> usize {
+    input
+        .chars()
+        .fold( 0, |a, c| {
+            (a << 1) | (c == '#') as usize
+        } )
+}
+
+named!(pub parse_header(&str) -> BitVec,
+    do_parse!(
+        tag!( "initial state: " )   >>
+        initial: rest               >>
+        (to_bitvec( initial ))
+    )
+);
+
+named!(pub parse_rule(&str) -> (usize, bool),
+    do_parse!(
+        when: take!( 5 )                            >>
+        tag!( " => " )                              >>
+        what: alt!( char!( '#' ) | char!( '.' ) )   >>
+        ((
+            to_bits( when ),
+            what == '#'
+        ))
+    )
+);
+
+/// Next generation of the row, trimmed of empty pots on both ends, together
+/// with how far its first pot moved to the left.
+pub fn one_step( mut state: BitVec, rules: &Rules ) -> (i64, BitVec) {
+    // NOTE: A plant can show up two pots past either end, and the window
+    // of five needs two more pots around that, so four empty pots on both ends
+    state.resize( state.len() + 8, false );
+    state.shift_end( 4 );
+
+    let mut now = 0;
+
+    for i in 0 .. 5 {
+        now <<= 1;
+        now |= state[ i ] as u8;
+    }
+
+    let mut new_state = state.clone();
+
+    for i in 0 .. state.len() - 4 {
+        now = (now | state[ i + 4 ] as u8) & 0x1f;
+        new_state.set( i + 2, rules[ now as usize ] );
+        now <<= 1;
+    }
+
+    let leading = new_state.leading_zeros();
+    let trailing = new_state.trailing_zeros();
+
+    new_state.drain( .. leading );
+    new_state.truncate( new_state.len().saturating_sub( trailing ) );
+
+    (4 - leading as i64, new_state)
+}
+
+/// Row of pots, `state[ 0 ]` being pot number `index_shift`.
+#[derive(Clone)]
+pub struct Plants {
+    pub state: BitVec,
+    pub rules: Rules,
+    pub index_shift: i64
+}
+
+impl Plants {
+    /// Sum of the numbers of all pots with a plant.
+    pub fn sum( &self ) -> i64 {
+        (0 .. self.state.len())
+            .filter( |&i| self.state[ i ] )
+            .map( |i| i as i64 + self.index_shift )
+            .sum::<i64>()
+    }
+}
+
+impl Simulation for Plants {
+    type Snapshot = (BitVec, i64);
+
+    fn step( &mut self ) {
+        let state = std::mem::take( &mut self.state );
+        let (shift, new_state) = one_step( state, &self.rules );
+
+        self.state = new_state;
+        self.index_shift -= shift;
+    }
+
+    fn snapshot( &self ) -> Self::Snapshot {
+        (self.state.clone(), self.index_shift)
+    }
+
+    fn restore( &mut self, snapshot: &Self::Snapshot ) {
+        self.state = snapshot.0.clone();
+        self.index_shift = snapshot.1;
+    }
+}
+
+impl Visualize for Plants {
+    fn render( &self, _width: usize, _height: usize ) -> Vec<String> {
+        let row = (0 .. self.state.len())
+                    .map( |i| if self.state[ i ] { '#' } else { '.' } )
+                    .collect::<String>();
+
+        vec![
+            format!( "row starts at pot {}, sum of pots with plants: {}", self.index_shift, self.sum() ),
+            String::new(),
+            row
+        ]
+    }
+}
+
+/// Initial row and rules, as given in the puzzle.
+pub fn parse_input<I: Iterator<Item=String>>( input: I ) -> Result<Plants, String> {
+    let (header, body) = inputs::header_body( input )?;
+    let state = match parse_header( &header ) {
+        Ok( (_, state) )    => state,
+        Err( _ )            => return Err( format!( "invalid header: '{}'", header ) )
+    };
+
+    let mut rules = [false; 32];
+
+    for line in &body {
+        match parse_rule( line ) {
+            Ok( ("", (rule, plant)) )   => rules[ rule ] = plant,
+            _                           => return Err( format!( "invalid rule: '{}'", line ) )
+        }
+    }
+
+    Ok( Plants {
+        state,
+        rules,
+        index_shift: 0
+    } )
+}
+
+/// Sum of the pots with a plant after 20 generations.
+pub fn solve_part1( plants: &Plants ) -> i64 {
+    let mut runner = Runner::new( plants.clone() );
+    runner.step_n( 20 );
+
+    runner.sim().sum()
+}
+
+/// Sum of the pots with a plant after `generations` generations.
+// Once the row repeats itself (up to a shift) the remaining generations only
+// move it further, so they are skipped in whole cycles.
+pub fn sum_after( plants: Plants, generations: usize ) -> i64 {
+    let mut runner = Runner::new( plants );
+    let mut cache = HashMap::<BitVec, (usize, i64)>::default();
+
+    while runner.tick() < generations {
+        runner.step();
+
+        let plants = runner.sim();
+        if let Some( &(old_i, old_shift) ) = cache.get( &plants.state ) {
+            let cycle_i = runner.tick();
+            let (rel_shift, cycle_len) = (plants.index_shift - old_shift, cycle_i - old_i);
+
+            info!( "generation {} repeats generation {} shifted by {}, cycle of length {}", cycle_i, old_i, rel_shift, cycle_len );
+
+            let remaining = generations - cycle_i;
+            runner.sim_mut().index_shift += rel_shift * (remaining / cycle_len) as i64;
+            runner.step_n( remaining % cycle_len );
+            break;
+        }
+
+        cache.insert( plants.state.clone(), (runner.tick(), plants.index_shift) );
+    }
+
+    runner.sim().sum()
+}
+
+/// Sum of the pots with a plant after fifty billion generations.
+pub fn solve_part2( plants: &Plants ) -> i64 {
+    const GENERATIONS: usize = 50_000_000_000;
+
+    sum_after( plants.clone(), GENERATIONS )
+}
+
+/// `solve_part1` straight from the input lines.
+pub fn part1<I: Iterator<Item=String>>( input: I ) -> Result<i64, String> {
+    Ok( solve_part1( &parse_input( input )? ) )
+}
+
+/// `solve_part2` straight from the input lines.
+pub fn part2<I: Iterator<Item=String>>( input: I ) -> Result<i64, String> {
+    Ok( solve_part2( &parse_input( input )? ) )
+}
+
+/// Reference: a plain set of pot numbers, grown one generation after another.
+pub fn reference_sum( input: &[String], generations: usize ) -> i64 {
+    let initial = input[ 0 ].strip_prefix( "initial state: " ).expect( "invalid header" );
+    let mut pots = initial
+                    .char_indices()
+                    .filter( |&(_, c)| c == '#' )
+                    .map( |(i, _)| i as i64 )
+                    .collect::<HashSet<_>>();
+
+    assert!( input[ 1 ].is_empty(), "missing empty line" );
+
+    let mut growing = HashSet::new();
+    for rule in &input[ 2 .. ] {
+        let valid = rule.len() == 10 && &rule[ 5 .. 9 ] == " => " &&
+                    rule[ .. 5 ].chars().chain( rule[ 9 .. ].chars() ).all( |c| c == '#' || c == '.' );
+
+        assert!( valid, "invalid rule" );
+        if rule.ends_with( '#' ) {
+            growing.insert( rule[ .. 5 ].to_string() );
+        }
+    }
+
+    for _ in 0 .. generations {
+        let (first, last) = match (pots.iter().min(), pots.iter().max()) {
+            (Some( &first ), Some( &last )) => (first, last),
+            _                               => break
+        };
+
+        pots = (first - 2 ..= last + 2)
+                .filter( |&i| {
+                    let around = (i - 2 ..= i + 2)
+                                    .map( |j| if pots.contains( &j ) { '#' } else { '.' } )
+                                    .collect::<String>();
+
+                    growing.contains( &around )
+                } )
+                .collect();
+    }
+
+    pots.iter().sum()
+}
+
+// NOTE: Generated rows settle within a thousand generations, so this many is
+// enough for part two to skip ahead
+const DIFF_GENERATIONS: usize = 2000;
+
+/// Optimised solutions paired with their references, for `diff-test`.
+pub const CHECKS: [diff::Check; 2] = [
+    ("part1", |input| part1( input.iter().cloned() ).map_or_else( |e| e, |sum| sum.to_string() ), |input| reference_sum( input, 20 ).to_string()),
+    ("part2", |input| parse_input( input.iter().cloned() ).map_or_else( |e| e, |plants| sum_after( plants, DIFF_GENERATIONS ).to_string() ), |input| reference_sum( input, DIFF_GENERATIONS ).to_string())
+];
+
+/// Saves the first couple of generations as a PNG image.
+// Every row of the image is a single generation, starting with the initial one.
+pub fn export<I: Iterator<Item=String>>( input: I, output: &str ) -> Result<(), Box<dyn std::error::Error>> {
+    const GENERATIONS: usize = 200;
+
+    let mut runner = Runner::new( parse_input( input )? );
+    runner.record();
+    runner.step_n( GENERATIONS );
+
+    let first = runner.trace().map( |(_, (_, shift))| *shift ).min().unwrap();
+    let last = runner.trace().map( |(_, (state, shift))| *shift + state.len() as i64 ).max().unwrap();
+
+    let mut image = Image::new( (last - first) as usize, GENERATIONS + 1 );
+
+    for (generation, (state, shift)) in runner.trace() {
+        for i in (0 .. state.len()).filter( |&i| state[ i ] ) {
+            image.set_signed( i as i64 + shift - first, generation as i64, export::GREEN );
+        }
+    }
+
+    image.scaled( 3 ).save( output )
+}
+
+// Whether the row ends up repeating itself (up to a shift) before it dies
+// out or grows out of hand.
+fn settles( plants: Plants ) -> bool {
+    let mut runner = Runner::new( plants );
+    let mut seen = HashSet::new();
+
+    for _ in 0 .. 1000 {
+        let state = &runner.sim().state;
+
+        if state.len() > 10_000 || ! (0 .. state.len()).any( |i| state[ i ] ) {
+            return false;
+        }
+
+        if ! seen.insert( state.clone() ) {
+            return true;
+        }
+
+        runner.step();
+    }
+
+    false
+}
+
+/// Random row with rules that make it settle into a cycle.
+// NOTE: Random rules easily wipe out every plant or grow chaotically, so
+// they are only kept once the row settles into a cycle, like in the puzzle.
+// Just like there, a lone plant on the edge of the window never grows a new
+// one, otherwise rows spread two pots a generation and hardly ever settle.
+pub fn generate( rng: &mut Rng, size: usize ) -> Vec<String> {
+    let pot = |plant: bool| if plant { '#' } else { '.' };
+
+    loop {
+        let initial = (0 .. size.max( 1 ))
+                        .map( |i| pot( i == 0 || rng.chance( 0.5 ) ) )
+                        .collect::<String>();
+
+        let mut input = vec![ format!( "initial state: {}", initial ), String::new() ];
+
+        for rule in 0 .. 32 {
+            let when = (0 .. 5).map( |bit| pot( (rule >> (4 - bit)) & 1 == 1 ) ).collect::<String>();
+            let grows = ! [0b00000, 0b00001, 0b10000].contains( &rule ) && rng.chance( 0.5 );
+            input.push( format!( "{} => {}", when, pot( grows ) ) );
+        }
+
+        if settles( parse_input( input.iter().cloned() ).expect( "generated rules are valid" ) ) {
+            return input;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc::gen::Rng;
+    use proptest::prelude::*;
+
+    const EXAMPLE: &str = "initial state: #..#.#..##......###...###
+
+...## => #
+..#.. => #
+.#... => #
+.#.#. => #
+.#.## => #
+.##.. => #
+.#### => #
+#.#.# => #
+#.### => #
+##.#. => #
+##.## => #
+###.. => #
+###.# => #
+####. => #";
+
+    fn example() -> impl Iterator<Item=String> {
+        EXAMPLE.lines().map( String::from )
+    }
+
+    #[test]
+    fn twenty_generations_of_the_example() {
+        assert_eq!( part1( example() ), Ok( 325 ) );
+    }
+
+    #[test]
+    fn malformed_input_is_an_error() {
+        let input = |lines: &[&str]| parse_input( lines.iter().map( |l| l.to_string() ) ).err();
+
+        assert_eq!( input( &[ "initial: #..#", "", "..#.. => #" ] ), Some( "invalid header: 'initial: #..#'".into() ) );
+        assert_eq!( input( &[ "initial state: #..#", "", "..#. => #" ] ), Some( "invalid rule: '..#. => #'".into() ) );
+        assert_eq!( input( &[ "initial state: #..#", "", "..#.. => ##" ] ), Some( "invalid rule: '..#.. => ##'".into() ) );
+        assert!( input( &[ "initial state: #..#", "..#.. => #" ] ).is_some() );
+        assert!( input( &[] ).is_some() );
+    }
+
+    #[test]
+    fn references_agree() {
+        assert!( diff::check( 8, 30, 0, generate, &CHECKS ).unwrap().is_none() );
+    }
+
+    #[test]
+    fn generated_rows_settle() {
+        for seed in 0 .. 8 {
+            let input = generate( &mut Rng::new( seed ), 40 );
+
+            assert_eq!( input.len(), 34 );
+            assert!( settles( parse_input( input.iter().cloned() ).unwrap() ) );
+            assert!( part2( input.into_iter() ).is_ok() );
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn parse_rule_reads_pattern_as_bits( when in "[#.]{5}", plant in prop::bool::ANY ) {
+            let line = format!( "{} => {}", when, if plant { '#' } else { '.' } );
+            let (rule, result) = parse_rule( &line ).unwrap().1;
+
+            let expected = when
+                            .chars()
+                            .enumerate()
+                            .filter( |&(_, c)| c == '#' )
+                            .map( |(i, _)| 1 << (4 - i) )
+                            .sum::<usize>();
+
+            prop_assert_eq!( rule, expected );
+            prop_assert_eq!( result, plant );
+        }
+
+        #[test]
+        fn header_keeps_every_pot( pots in "#[#.]{0,40}" ) {
+            let state = parse_header( &format!( "initial state: {}", pots ) ).unwrap().1;
+
+            prop_assert_eq!( state.len(), pots.len() );
+            for (i, c) in pots.chars().enumerate() {
+                prop_assert_eq!( state[ i ], c == '#' );
+            }
+        }
+
+        #[test]
+        fn rewinding_restores_the_row( ticks in 0 .. 60usize, back in 0 .. 60usize ) {
+            let mut runner = Runner::new( parse_input( example() ).unwrap() );
+            runner.record();
+            runner.step_n( ticks );
+
+            let back = back.min( ticks );
+            let mut expected = Runner::new( parse_input( example() ).unwrap() );
+            expected.step_n( ticks - back );
+
+            prop_assert!( runner.rewind( back ) );
+            prop_assert_eq!( runner.sim().sum(), expected.sim().sum() );
+            prop_assert_eq!( runner.sim().index_shift, expected.sim().index_shift );
+        }
+    }
+}
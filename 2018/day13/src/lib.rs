@@ -0,0 +1,486 @@
+//! Mine Cart Madness: carts such as `>` riding along tracks of `-|/\+`.
+
+use std::collections::HashMap;
+
+use aoc::export::{self, Animation, Image};
+use aoc::gen::Rng;
+use aoc::info;
+use aoc::sim::{Runner, Simulation};
+use aoc::viz::Visualize;
+
+pub type Scalar = i64;
+pub type Vector = (Scalar, Scalar);
+
+/// Cart with the direction it moves in, `counter` tells where it turns at the
+/// next crossroads.
+#[derive(Debug, Clone)]
+pub struct Cart {
+    pub position: Vector,
+    pub velocity: Vector,
+    pub alive: bool,
+    pub counter: usize
+}
+
+impl Cart {
+    pub fn new( position: Vector, velocity: Vector ) -> Self {
+        Cart {
+            position,
+            velocity,
+            alive: true,
+            counter: 0
+        }
+    }
+
+    /// Cart facing the way `c` (one of `^v<>`) points.
+    pub fn from_char( position: Vector, c: char ) -> Self {
+        let velocity = match c {
+            '^' => (0, -1),
+            'v' => (0,  1),
+            '>' => (1,  0),
+            '<' => (-1, 0),
+            _   => panic!( "Invalid character" )
+        };
+
+        Self::new( position, velocity )
+    }
+
+    /// Character the cart is drawn with.
+    pub fn to_char( &self ) -> char {
+        match self.velocity {
+            (0, -1) => '^',
+            (0,  1) => 'v',
+            (1,  0) => '>',
+            _       => '<'
+        }
+    }
+
+    /// Moves the cart by a single piece of track.
+    pub fn step( &mut self ) {
+        self.position.0 += self.velocity.0;
+        self.position.1 += self.velocity.1;
+    }
+
+    /// Turns left, goes straight or turns right, one after another.
+    pub fn crossroad( &mut self ) {
+        if self.counter == 0 {
+            self.turn_left();
+        } else if self.counter == 2 {
+            self.turn_right();
+        }
+
+        self.counter = (self.counter + 1) % 3;
+    }
+
+    pub fn turn_right( &mut self ) {
+        self.velocity = (
+            -self.velocity.1,
+            self.velocity.0
+        );
+    }
+
+    pub fn turn_left( &mut self ) {
+        self.velocity = (
+            self.velocity.1,
+            -self.velocity.0
+        );
+    }
+}
+
+/// Tracks with all the carts on them, and where they crashed so far.
+#[derive(Clone)]
+pub struct Mine {
+    pub carts: Vec<Cart>,
+    pub map: HashMap<Vector, char>,
+    pub track: Vec<String>,
+    pub collisions: Vec<Vector>
+}
+
+impl Mine {
+    /// Carts which haven't crashed yet.
+    pub fn alive( &self ) -> impl Iterator<Item=&Cart> {
+        self.carts.iter().filter( |c| c.alive )
+    }
+}
+
+impl Simulation for Mine {
+    type Snapshot = (Vec<Cart>, Vec<Vector>);
+
+    fn step( &mut self ) {
+        let carts = &mut self.carts;
+
+        self.collisions.clear();
+        carts.sort_by_key( |c| (c.position.1, c.position.0) );
+
+        // NOTE: Holds every living cart, so a cart can also run into one
+        // that hasn't moved yet in this tick (e.g. one right after another)
+        let mut positions = carts
+                                .iter()
+                                .enumerate()
+                                .filter( |(_, c)| c.alive )
+                                .map( |(i, c)| (c.position, i) )
+                                .collect::<HashMap<Vector, usize>>();
+
+        for i in 0 .. carts.len() {
+            if ! carts[ i ].alive {
+                continue;
+            }
+
+            positions.remove( &carts[ i ].position );
+
+            if let Some( c ) = self.map.get( &carts[ i ].position ) {
+                match c {
+                    '/' => {
+                        if carts[ i ].velocity.1 != 0 {
+                            carts[ i ].turn_right();
+                        } else {
+                            carts[ i ].turn_left();
+                        }
+                    },
+                    '\\' => {
+                        if carts[ i ].velocity.0 != 0 {
+                            carts[ i ].turn_right();
+                        } else {
+                            carts[ i ].turn_left();
+                        }
+                    },
+                    '+' => carts[ i ].crossroad(),
+                    _ => {}
+                }
+            }
+
+            carts[ i ].step();
+
+            if let Some( old_i ) = positions.remove( &carts[ i ].position ) {
+                carts[ old_i ].alive = false;
+                carts[ i ].alive = false;
+                self.collisions.push( carts[ i ].position );
+            } else {
+                positions.insert( carts[ i ].position, i );
+            }
+        }
+    }
+
+    fn snapshot( &self ) -> Self::Snapshot {
+        (self.carts.clone(), self.collisions.clone())
+    }
+
+    fn restore( &mut self, snapshot: &Self::Snapshot ) {
+        self.carts = snapshot.0.clone();
+        self.collisions = snapshot.1.clone();
+    }
+}
+
+impl Visualize for Mine {
+    fn render( &self, _width: usize, _height: usize ) -> Vec<String> {
+        let mut grid = self.track
+                        .iter()
+                        .map( |l| l.chars().collect::<Vec<_>>() )
+                        .collect::<Vec<_>>();
+
+        let carts = self.alive().map( |c| (c.position, c.to_char()) );
+        let crashes = self.collisions.iter().map( |&p| (p, 'X') );
+
+        for ((x, y), c) in carts.chain( crashes ) {
+            if let Some( cell ) = grid.get_mut( y as usize ).and_then( |l| l.get_mut( x as usize ) ) {
+                *cell = c;
+            }
+        }
+
+        grid.into_iter().map( |l| l.into_iter().collect() ).collect()
+    }
+
+    fn event( &self ) -> Option<String> {
+        if self.collisions.is_empty() {
+            return None;
+        }
+
+        let at = self.collisions
+                    .iter()
+                    .map( |(x, y)| format!( "{},{}", x, y ) )
+                    .collect::<Vec<_>>();
+
+        Some( format!( "collision at {} ({} carts left)", at.join( " " ), self.alive().count() ) )
+    }
+}
+
+/// Mine drawn by the input, with carts taken off the tracks.
+pub fn parse_input<I: Iterator<Item=String>>( input: I ) -> Mine {
+    let mut carts = vec![];
+    let mut map = HashMap::default();
+    let mut track = vec![];
+
+    for (y, l) in input.enumerate() {
+        for (x, c) in l.chars().enumerate() {
+            match c {
+                '>' | '<' | 'v' | '^'   => { carts.push( Cart::from_char( (x as i64, y as i64), c ) ); },
+                '/' | '\\' | '+'        => { map.insert( (x as i64, y as i64), c ); },
+                _                       => continue
+            }
+        }
+
+        // NOTE: Carts always start on a straight piece of the track
+        track.push( l.replace( ['<', '>'], "-" ).replace( ['^', 'v'], "|" ) );
+    }
+
+    Mine {
+        carts,
+        map,
+        track,
+        collisions: vec![]
+    }
+}
+
+// Same as `Runner::run_until`, tracing every crash on the way.
+fn run_until<P: FnMut( &Mine ) -> bool>( runner: &mut Runner<Mine>, mut done: P ) -> usize {
+    while ! done( runner.sim() ) {
+        runner.step();
+
+        for (x, y) in &runner.sim().collisions {
+            info!( "tick {}: carts crash at {},{}, {} left", runner.tick(), x, y, runner.sim().alive().count() );
+        }
+    }
+
+    runner.tick()
+}
+
+/// `x,y` of the first crash.
+pub fn solve_part1( mine: &Mine ) -> String {
+    let mut runner = Runner::new( mine.clone() );
+    run_until( &mut runner, |m| ! m.collisions.is_empty() );
+
+    let (x, y) = runner.sim().collisions[ 0 ];
+    format!( "{},{}", x, y )
+}
+
+/// `x,y` of the last cart left once all the others crashed.
+pub fn solve_part2( mine: &Mine ) -> String {
+    let mut runner = Runner::new( mine.clone() );
+    run_until( &mut runner, |m| m.alive().count() <= 1 );
+
+    let (x, y) = runner
+                    .sim()
+                    .alive()
+                    .next()
+                    .map( |c| c.position )
+                    .unwrap();
+
+    format!( "{},{}", x, y )
+}
+
+/// `solve_part1` straight from the input lines.
+pub fn part1<I: Iterator<Item=String>>( input: I ) -> String {
+    solve_part1( &parse_input( input ) )
+}
+
+/// `solve_part2` straight from the input lines.
+pub fn part2<I: Iterator<Item=String>>( input: I ) -> String {
+    solve_part2( &parse_input( input ) )
+}
+
+/// Animates carts until the last one is left, skipping ticks on long runs.
+pub fn export<I: Iterator<Item=String>>( input: I, output: &str ) -> Result<(), Box<dyn std::error::Error>> {
+    const MAX_FRAMES: usize = 500;
+
+    let mut runner = Runner::new( parse_input( input ) );
+
+    let track = &runner.sim().track;
+    let (width, height) = (track.iter().map( |l| l.len() ).max().unwrap_or( 0 ), track.len());
+
+    let track = Image::from_fn( width, height, |x, y| {
+        match track[ y ].as_bytes().get( x ) {
+            None | Some( b' ' ) => export::BLACK,
+            _                   => export::GRAY
+        }
+    } );
+
+    runner.record();
+    runner.run_until( |m| m.alive().count() <= 1 );
+
+    let every = runner.tick() / MAX_FRAMES + 1;
+    let mut crashes = vec![];
+    let mut animation = Animation::new( 5 );
+
+    for (tick, (carts, collisions)) in runner.trace() {
+        crashes.extend( collisions.iter().cloned() );
+
+        if tick % every != 0 && tick != runner.tick() {
+            continue;
+        }
+
+        let mut frame = track.clone();
+
+        for &(x, y) in &crashes {
+            frame.set_signed( x, y, export::RED );
+        }
+
+        for cart in carts.iter().filter( |c| c.alive ) {
+            frame.set_signed( cart.position.0, cart.position.1, export::YELLOW );
+        }
+
+        animation.push( frame.scaled( 3 ) );
+    }
+
+    animation.save( output )
+}
+
+/// Rectangles which never share a row or a column, so tracks only ever meet
+/// at crossroads. An odd number of carts is scattered over straight pieces
+/// and layouts where they don't crash down to a single cart are thrown away.
+pub fn generate( rng: &mut Rng, size: usize ) -> Vec<String> {
+    const MAX_TICKS: usize = 20_000;
+
+    let size = size.max( 8 );
+
+    loop {
+        let mut grid = vec![ vec![ ' '; size ]; size ];
+        let (mut xs, mut ys) = ((0 .. size).collect::<Vec<_>>(), (0 .. size).collect::<Vec<_>>());
+        rng.shuffle( &mut xs );
+        rng.shuffle( &mut ys );
+
+        for (x, y) in xs.chunks_exact( 2 ).zip( ys.chunks_exact( 2 ) ).take( size / 8 ) {
+            let (x1, x2) = (x[ 0 ].min( x[ 1 ] ), x[ 0 ].max( x[ 1 ] ));
+            let (y1, y2) = (y[ 0 ].min( y[ 1 ] ), y[ 0 ].max( y[ 1 ] ));
+
+            for &y in &[y1, y2] {
+                for cell in &mut grid[ y ][ x1 ..= x2 ] {
+                    *cell = if *cell == '|' { '+' } else { '-' };
+                }
+            }
+
+            for row in &mut grid[ y1 ..= y2 ] {
+                for &x in &[x1, x2] {
+                    row[ x ] = if row[ x ] == '-' { '+' } else { '|' };
+                }
+            }
+
+            grid[ y1 ][ x1 ] = '/';
+            grid[ y1 ][ x2 ] = '\\';
+            grid[ y2 ][ x1 ] = '\\';
+            grid[ y2 ][ x2 ] = '/';
+        }
+
+        let mut straight = (0 .. size)
+                            .flat_map( |y| (0 .. size).map( move |x| (x, y) ) )
+                            .filter( |&(x, y)| grid[ y ][ x ] == '-' || grid[ y ][ x ] == '|' )
+                            .collect::<Vec<_>>();
+
+        let carts = (straight.len() / 20).clamp( 1, 20 ) * 2 + 1;
+        if straight.len() < carts {
+            continue;
+        }
+
+        rng.shuffle( &mut straight );
+
+        for &(x, y) in &straight[ .. carts ] {
+            grid[ y ][ x ] = if grid[ y ][ x ] == '-' { *rng.pick( &['<', '>'] ) } else { *rng.pick( &['^', 'v'] ) };
+        }
+
+        let input = grid.into_iter().map( |l| l.into_iter().collect::<String>() ).collect::<Vec<_>>();
+        let mut runner = Runner::new( parse_input( input.iter().cloned() ) );
+
+        while runner.sim().alive().count() > 1 && runner.tick() < MAX_TICKS {
+            runner.step();
+        }
+
+        if runner.sim().alive().count() == 1 {
+            return input;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc::gen::Rng;
+    use proptest::prelude::*;
+
+    fn cart() -> impl Strategy<Value=Cart> {
+        prop::sample::select( vec![ '^', 'v', '<', '>' ] ).prop_map( |c| Cart::from_char( (0, 0), c ) )
+    }
+
+    fn mine( lines: &[&str] ) -> Mine {
+        parse_input( lines.iter().map( |l| l.to_string() ) )
+    }
+
+    #[test]
+    fn examples() {
+        let first = [
+            r"/->-\        ",
+            r"|   |  /----\",
+            r"| /-+--+-\  |",
+            r"| | |  | v  |",
+            r"\-+-/  \-+--/",
+            r"  \------/   "
+        ];
+
+        let second = [
+            r"/>-<\  ",
+            r"|   |  ",
+            r"| /<+-\",
+            r"| | | v",
+            r"\>+</ |",
+            r"  |   ^",
+            r"  \<->/"
+        ];
+
+        assert_eq!( part1( first.iter().map( |l| l.to_string() ) ).to_string(), "7,3" );
+        assert_eq!( part2( second.iter().map( |l| l.to_string() ) ).to_string(), "6,4" );
+    }
+
+    #[test]
+    fn generated_tracks_leave_one_cart() {
+        for seed in 0 .. 8 {
+            let input = generate( &mut Rng::new( seed ), 10 + seed as usize * 5 );
+
+            part1( input.iter().cloned() );
+            part2( input.into_iter() );
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn turning_is_reversible( mut cart in cart() ) {
+            let velocity = cart.velocity;
+
+            cart.turn_left();
+            prop_assert_ne!( cart.velocity, velocity );
+            cart.turn_right();
+            prop_assert_eq!( cart.velocity, velocity );
+
+            for _ in 0 .. 4 {
+                cart.turn_right();
+            }
+
+            prop_assert_eq!( cart.velocity, velocity );
+        }
+
+        #[test]
+        fn crossroads_cycle_every_three_visits( mut cart in cart(), visits in 0 .. 10usize ) {
+            let velocity = cart.velocity;
+
+            for _ in 0 .. visits * 3 {
+                cart.crossroad();
+            }
+
+            prop_assert_eq!( cart.velocity, velocity );
+            prop_assert_eq!( cart.counter, 0 );
+            prop_assert_eq!( Cart::from_char( (0, 0), cart.to_char() ).velocity, velocity );
+        }
+
+        #[test]
+        fn head_on_carts_collide_halfway( left in 0 .. 20usize, distance in 1 .. 20usize ) {
+            let mut line = vec![ '-'; left + distance + 5 ];
+            line[ left ] = '>';
+            line[ left + distance ] = '<';
+
+            let line = line.into_iter().collect::<String>();
+            let mut runner = Runner::new( mine( &[ &line ] ) );
+            let tick = runner.run_until( |m| ! m.collisions.is_empty() );
+
+            let halfway = distance.div_ceil( 2 );
+            prop_assert_eq!( tick, halfway );
+            prop_assert_eq!( runner.sim().collisions.clone(), vec![ ((left + halfway) as i64, 0) ] );
+            prop_assert_eq!( runner.sim().alive().count(), 0 );
+        }
+    }
+}
@@ -0,0 +1,208 @@
+//! Chocolate Charts: the scoreboard of recipes two elves keep extending.
+
+use aoc::diff;
+use aoc::gen::Rng;
+use aoc::sim::{Runner, Simulation};
+
+/// Scores of all recipes so far and the recipes both elves are at.
+pub struct Scoreboard {
+    pub recipes: Vec<u8>,
+    pub elves: (usize, usize)
+}
+
+impl Scoreboard {
+    /// Scoreboard the elves start with, `37`.
+    pub fn new() -> Self {
+        Scoreboard {
+            recipes: vec![3u8, 7u8],
+            elves: (0, 1)
+        }
+    }
+}
+
+impl Default for Scoreboard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Simulation for Scoreboard {
+    // NOTE: Recipes are only ever appended, so their count is enough
+    type Snapshot = (usize, (usize, usize));
+
+    fn step( &mut self ) {
+        let recipes = &mut self.recipes;
+        let (elf_a, elf_b) = self.elves;
+        let recipe = recipes[ elf_a ] + recipes[ elf_b ];
+
+        if recipe >= 10 {
+            recipes.push( recipe / 10 );
+        }
+        recipes.push( recipe % 10 );
+
+        self.elves = (
+            (elf_a + recipes[ elf_a ] as usize + 1) % recipes.len(),
+            (elf_b + recipes[ elf_b ] as usize + 1) % recipes.len()
+        );
+    }
+
+    fn snapshot( &self ) -> Self::Snapshot {
+        (self.recipes.len(), self.elves)
+    }
+
+    fn restore( &mut self, snapshot: &Self::Snapshot ) {
+        self.recipes.truncate( snapshot.0 );
+        self.elves = snapshot.1;
+    }
+}
+
+/// Puzzle input, a single line read as a number by part one and as digits by
+/// part two.
+pub fn parse<I: Iterator<Item=String>>( input: I ) -> String {
+    input.into_iter().next().unwrap()
+}
+
+/// Scores of the ten recipes after the given number of recipes.
+pub fn solve_part1( input: &str ) -> String {
+    let count = input.parse::<usize>().unwrap();
+
+    let mut runner = Runner::new( Scoreboard::new() );
+    runner.run_until( |s| s.recipes.len() >= count + 10 );
+
+    runner.sim().recipes[ count.. ]
+        .iter()
+        .take( 10 )
+        .map( |n| (n + 0x30) as char )
+        .collect::<String>()
+}
+
+/// Number of recipes before the given digits first show up.
+pub fn solve_part2( input: &str ) -> usize {
+    let search = input
+                    .chars()
+                    .map( |c| c as u8 - 0x30 )
+                    .collect::<Vec<_>>();
+
+    let mut runner = Runner::new( Scoreboard::new() );
+    let mut seen = 0;
+
+    loop {
+        let recipes = &runner.sim().recipes;
+
+        // NOTE: A step adds up to two recipes, so the match may end on either
+        for end in seen + 1 ..= recipes.len() {
+            if recipes[ .. end ].ends_with( &search ) {
+                return end - search.len();
+            }
+        }
+
+        seen = recipes.len();
+        runner.step();
+    }
+}
+
+/// `solve_part1` straight from the input lines.
+pub fn part1<I: Iterator<Item=String>>( input: I ) -> String {
+    solve_part1( &parse( input ) )
+}
+
+/// `solve_part2` straight from the input lines.
+pub fn part2<I: Iterator<Item=String>>( input: I ) -> usize {
+    solve_part2( &parse( input ) )
+}
+
+/// Reference: keeps doubling the scoreboard until the digits show up anywhere
+/// in it, instead of only looking at the newest recipes.
+pub fn reference_part2( input: &[String] ) -> String {
+    let search = input[ 0 ].chars().map( |c| c as u8 - 0x30 ).collect::<Vec<_>>();
+    let mut scoreboard = Scoreboard::new();
+    let mut limit = 1024;
+
+    loop {
+        while scoreboard.recipes.len() < limit {
+            scoreboard.step();
+        }
+
+        if let Some( i ) = scoreboard.recipes.windows( search.len() ).position( |w| w == &search[ .. ] ) {
+            return i.to_string();
+        }
+
+        limit *= 2;
+    }
+}
+
+/// Optimised solutions paired with their references, for `diff-test`.
+pub const CHECKS: [diff::Check; 1] = [
+    ("part2", |input| part2( input.iter().cloned() ).to_string(), reference_part2)
+];
+
+/// Digits are cut out of the scoreboard itself, so part two is guaranteed to
+/// find them.
+pub fn generate( rng: &mut Rng, size: usize ) -> Vec<String> {
+    let size = size.max( 1 );
+    let end = size + rng.below( 1_000_000 );
+
+    let mut runner = Runner::new( Scoreboard::new() );
+    runner.run_until( |s| s.recipes.len() >= end );
+
+    vec![ runner.sim().recipes[ end - size .. end ].iter().map( |n| (n + 0x30) as char ).collect() ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn line( s: &str ) -> std::iter::Once<String> {
+        std::iter::once( s.to_string() )
+    }
+
+    #[test]
+    fn examples() {
+        for &(count, scores) in &[ ("9", "5158916779"), ("5", "0124515891"), ("18", "9251071085"), ("2018", "5941429882") ] {
+            assert_eq!( part1( line( count ) ).to_string(), scores );
+        }
+
+        for &(search, count) in &[ ("51589", "9"), ("01245", "5"), ("92510", "18"), ("59414", "2018") ] {
+            assert_eq!( part2( line( search ) ).to_string(), count );
+        }
+    }
+
+    #[test]
+    fn references_agree() {
+        assert!( diff::check( 6, 6, 0, generate, &CHECKS ).unwrap().is_none() );
+    }
+
+    proptest! {
+        #[test]
+        fn part2_finds_first_occurrence( start in 0 .. 2000usize, len in 1 .. 6usize ) {
+            let mut scoreboard = Scoreboard::new();
+            while scoreboard.recipes.len() < start + len {
+                scoreboard.step();
+            }
+
+            let search = scoreboard.recipes[ start .. start + len ].to_vec();
+            let first = scoreboard.recipes.windows( len ).position( |w| w == &search[ .. ] ).unwrap();
+            let search = search.iter().map( |&n| (n + 0x30) as char ).collect::<String>();
+
+            prop_assert_eq!( part2( line( &search ) ).to_string(), first.to_string() );
+        }
+
+        #[test]
+        fn restore_rewinds_the_scoreboard( ticks in 0 .. 500usize, back in 0 .. 500usize ) {
+            let mut runner = Runner::new( Scoreboard::new() );
+            runner.record();
+            runner.step_n( ticks );
+
+            let back = back.min( ticks );
+            let mut expected = Scoreboard::new();
+            for _ in 0 .. ticks - back {
+                expected.step();
+            }
+
+            prop_assert!( runner.rewind( back ) );
+            prop_assert_eq!( &runner.sim().recipes, &expected.recipes );
+            prop_assert_eq!( runner.sim().elves, expected.elves );
+        }
+    }
+}
@@ -0,0 +1,577 @@
+//! Inventory Management System: box IDs such as `abcdef`.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::str::FromStr;
+
+use aoc::diff;
+use aoc::gen::Rng;
+
+/// Box IDs, one per line.
+pub fn parse<I: Iterator<Item=String>>( input: I ) -> Vec<String> {
+    input.collect()
+}
+
+/// How many times each letter of the ID shows up.
+pub fn histogram( id: &str ) -> BTreeMap<char, usize> {
+    id.chars().fold( BTreeMap::default(), |mut a, e| {
+        *a.entry( e ).or_insert( 0 ) += 1;
+        a
+    } )
+}
+
+/// Checksum of part one, for any letter counts instead of just 2 and 3.
+#[derive(Debug, PartialEq)]
+pub struct Checksum {
+    /// Every count with the IDs (as indices) having some letter exactly
+    /// that many times.
+    pub ids: Vec<(usize, Vec<usize>)>,
+    /// Number of IDs for each count multiplied together, `None` if that
+    /// doesn't fit.
+    pub product: Option<u128>
+}
+
+/// Checksum of the IDs over the given letter counts.
+pub fn checksum( ids: &[String], counts: &[usize] ) -> Checksum {
+    let mut found = counts.iter().map( |&count| (count, vec![]) ).collect::<Vec<_>>();
+
+    for (i, id) in ids.iter().enumerate() {
+        let local = histogram( id ).into_values().collect::<HashSet<_>>();
+
+        for (count, ids) in found.iter_mut() {
+            if local.contains( count ) {
+                ids.push( i );
+            }
+        }
+    }
+
+    Checksum {
+        product: found.iter().try_fold( 1u128, |product, (_, ids)| product.checked_mul( ids.len() as u128 ) ),
+        ids: found
+    }
+}
+
+impl Checksum {
+    /// Report for people: the IDs per count, the product and the letters
+    /// of every ID along with the counts it's in.
+    pub fn report( &self, ids: &[String] ) -> String {
+        let mut lines = vec![];
+
+        for (count, found) in &self.ids {
+            let names = found.iter().map( |&i| ids[ i ].as_str() ).collect::<Vec<_>>();
+            lines.push( format!( "count {}: {} IDs ({})", count, found.len(), names.join( " " ) ) );
+        }
+
+        match self.product {
+            Some( product ) => lines.push( format!( "product: {}", product ) ),
+            None            => lines.push( "product: too big".to_string() )
+        }
+
+        lines.push( String::new() );
+
+        let width = ids.iter().map( |id| id.chars().count() ).max().unwrap_or( 0 );
+        for (i, id) in ids.iter().enumerate() {
+            let letters = histogram( id ).into_iter().map( |(c, n)| format!( "{}={}", c, n ) ).collect::<Vec<_>>();
+            let counts = self.ids.iter().filter( |(_, found)| found.contains( &i ) ).map( |(c, _)| c.to_string() ).collect::<Vec<_>>();
+
+            let mut line = format!( "{:<width$}  {}", id, letters.join( " " ), width = width );
+            if ! counts.is_empty() {
+                line.push_str( &format!( "  -> {}", counts.join( ", " ) ) );
+            }
+
+            lines.push( line );
+        }
+
+        lines.join( "\n" )
+    }
+}
+
+/// Checksum: IDs with some letter exactly twice times IDs with some letter
+/// exactly three times.
+pub fn solve_part1( ids: &[String] ) -> u128 {
+    checksum( ids, &[ 2, 3 ] ).product.expect( "two counts of IDs always fit" )
+}
+
+/// Position of the only letter `a` and `b` differ at, if there is exactly one.
+pub fn differ_by_one( a: &str, b: &str ) -> Option<usize> {
+    let mut found = None;
+
+    for (i, (x, y)) in a.chars().zip( b.chars() ).enumerate() {
+        if x != y {
+            if found.is_none() {
+                found = Some( i );
+            } else {
+                return None;
+            }
+        }
+    }
+
+    found
+}
+
+/// Every pair of IDs of the same length which differ by a single letter, as
+/// their indices `i < j` and the position of that letter, in order.
+// NOTE: Each ID is hashed once as a polynomial of its letters. Masking out a
+// position subtracts that letter's term from the hash, so every ID with
+// every position masked out takes O(n * L) overall. Only IDs colliding at
+// the same position can differ just there, and they are still compared
+// letter by letter as hashes may collide by chance.
+pub fn close_pairs( ids: &[String] ) -> Vec<(usize, usize, usize)> {
+    const BASE: u64 = 0x100000001b3;
+
+    let ids = ids.iter().map( |id| id.chars().collect::<Vec<_>>() ).collect::<Vec<_>>();
+    let longest = ids.iter().map( Vec::len ).max().unwrap_or( 0 );
+
+    let mut powers = vec![ 1u64; longest + 1 ];
+    for i in 1 ..= longest {
+        powers[ i ] = powers[ i - 1 ].wrapping_mul( BASE );
+    }
+
+    let hashes = ids
+                    .iter()
+                    .map( |id| id.iter().fold( 0u64, |h, &c| h.wrapping_mul( BASE ).wrapping_add( c as u64 + 1 ) ) )
+                    .collect::<Vec<_>>();
+
+    let mut pairs = vec![];
+    let mut buckets = HashMap::<(usize, u64), Vec<usize>>::default();
+
+    for k in 0 .. longest {
+        buckets.clear();
+
+        for (i, id) in ids.iter().enumerate().filter( |(_, id)| id.len() > k ) {
+            let masked = hashes[ i ].wrapping_sub( (id[ k ] as u64 + 1).wrapping_mul( powers[ id.len() - 1 - k ] ) );
+            buckets.entry( (id.len(), masked) ).or_default().push( i );
+        }
+
+        for bucket in buckets.values().filter( |b| b.len() > 1 ) {
+            for (n, &i) in bucket.iter().enumerate() {
+                for &j in &bucket[ n + 1 .. ] {
+                    let (a, b) = (&ids[ i ], &ids[ j ]);
+
+                    if a[ k ] != b[ k ] && a[ .. k ] == b[ .. k ] && a[ k + 1 .. ] == b[ k + 1 .. ] {
+                        pairs.push( (i, j, k) );
+                    }
+                }
+            }
+        }
+    }
+
+    pairs.sort_unstable();
+    pairs
+}
+
+/// Common letters of every two IDs which differ by a single letter, one pair
+/// per line. The puzzle promises there's exactly one such pair.
+pub fn solve_part2( strings: &[String] ) -> String {
+    close_pairs( strings )
+        .into_iter()
+        .map( |(i, _, k)| {
+            strings[ i ]
+                .chars()
+                .enumerate()
+                .filter_map( |(i, c)| if i != k { Some( c ) } else { None } )
+                .collect::<String>()
+        } )
+        .collect::<Vec<_>>()
+        .join( "\n" )
+}
+
+/// How far apart two IDs are.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Metric {
+    /// Letters differing at the same position. IDs of different lengths
+    /// are infinitely far apart.
+    Hamming,
+    /// Letters inserted, removed or replaced to get from one ID to the other.
+    Levenshtein
+}
+
+impl Metric {
+    pub fn distance( self, a: &[char], b: &[char] ) -> usize {
+        match self {
+            Metric::Hamming if a.len() != b.len()   => usize::MAX,
+            Metric::Hamming                         => a.iter().zip( b ).filter( |(x, y)| x != y ).count(),
+            Metric::Levenshtein                     => {
+                let mut previous = (0 ..= b.len()).collect::<Vec<_>>();
+                let mut current = vec![ 0; b.len() + 1 ];
+
+                for (i, x) in a.iter().enumerate() {
+                    current[ 0 ] = i + 1;
+
+                    for (j, y) in b.iter().enumerate() {
+                        let replace = previous[ j ] + (x != y) as usize;
+                        current[ j + 1 ] = replace.min( previous[ j + 1 ] + 1 ).min( current[ j ] + 1 );
+                    }
+
+                    std::mem::swap( &mut previous, &mut current );
+                }
+
+                previous[ b.len() ]
+            }
+        }
+    }
+}
+
+impl FromStr for Metric {
+    type Err = String;
+
+    fn from_str( s: &str ) -> Result<Self, Self::Err> {
+        match s {
+            "hamming"       => Ok( Metric::Hamming ),
+            "levenshtein"   => Ok( Metric::Levenshtein ),
+            _               => Err( format!( "unknown metric '{}', pick hamming or levenshtein", s ) )
+        }
+    }
+}
+
+// NOTE: Children are keyed by their distance from the parent, so by the
+// triangle inequality only the ones `k` around the query's distance can be
+// within `k` of it
+struct BkNode {
+    id: usize,
+    children: HashMap<usize, usize>
+}
+
+struct BkTree<'a> {
+    ids: &'a [Vec<char>],
+    metric: Metric,
+    nodes: Vec<BkNode>
+}
+
+impl<'a> BkTree<'a> {
+    fn new( ids: &'a [Vec<char>], metric: Metric ) -> Self {
+        let mut tree = BkTree { ids, metric, nodes: vec![] };
+        for id in 0 .. ids.len() {
+            tree.insert( id );
+        }
+
+        tree
+    }
+
+    fn insert( &mut self, id: usize ) {
+        let mut node = 0;
+        self.nodes.push( BkNode { id, children: HashMap::default() } );
+
+        if self.nodes.len() == 1 {
+            return;
+        }
+
+        loop {
+            let distance = self.metric.distance( &self.ids[ self.nodes[ node ].id ], &self.ids[ id ] );
+
+            match self.nodes[ node ].children.get( &distance ) {
+                Some( &child )  => node = child,
+                None            => {
+                    let new = self.nodes.len() - 1;
+                    self.nodes[ node ].children.insert( distance, new );
+                    return;
+                }
+            }
+        }
+    }
+
+    fn within( &self, query: &[char], k: usize ) -> Vec<usize> {
+        let mut found = vec![];
+        let mut stack = if self.nodes.is_empty() { vec![] } else { vec![ 0 ] };
+
+        while let Some( node ) = stack.pop() {
+            let BkNode { id, children } = &self.nodes[ node ];
+            let distance = self.metric.distance( &self.ids[ *id ], query );
+
+            if distance <= k {
+                found.push( *id );
+            }
+
+            let range = distance.saturating_sub( k ) ..= distance.saturating_add( k );
+            stack.extend( children.iter().filter( |(d, _)| range.contains( d ) ).map( |(_, &child)| child ) );
+        }
+
+        found
+    }
+}
+
+/// Groups of IDs linked by chains of IDs at most `k` apart, leaving out the
+/// ones close to no other. Each group is a sorted list of indices, groups go
+/// by their first one.
+pub fn clusters( ids: &[String], metric: Metric, k: usize ) -> Vec<Vec<usize>> {
+    fn find( parents: &mut [usize], i: usize ) -> usize {
+        if parents[ i ] != i {
+            parents[ i ] = find( parents, parents[ i ] );
+        }
+
+        parents[ i ]
+    }
+
+    let ids = ids.iter().map( |id| id.chars().collect::<Vec<_>>() ).collect::<Vec<_>>();
+    let tree = BkTree::new( &ids, metric );
+    let mut parents = (0 .. ids.len()).collect::<Vec<_>>();
+
+    for (i, id) in ids.iter().enumerate() {
+        for j in tree.within( id, k ) {
+            let (a, b) = (find( &mut parents, i ), find( &mut parents, j ));
+            parents[ a.max( b ) ] = a.min( b );
+        }
+    }
+
+    let mut groups = HashMap::<usize, Vec<usize>>::default();
+    for i in 0 .. ids.len() {
+        let root = find( &mut parents, i );
+        groups.entry( root ).or_default().push( i );
+    }
+
+    let mut clusters = groups.into_values().filter( |g| g.len() > 1 ).collect::<Vec<_>>();
+    clusters.sort_unstable();
+    clusters
+}
+
+/// Metric used by `clusters` unless told otherwise.
+pub const METRIC: &str = "hamming";
+
+/// Distance `clusters` links IDs within unless told otherwise, the one of
+/// part two.
+pub const DISTANCE: usize = 1;
+
+/// `solve_part1` straight from the input lines.
+pub fn part1<I: Iterator<Item=String>>( input: I ) -> u128 {
+    solve_part1( &parse( input ) )
+}
+
+/// `solve_part2` straight from the input lines.
+pub fn part2<I: Iterator<Item=String>>( input: I ) -> String {
+    solve_part2( &parse( input ) )
+}
+
+/// Reference: every letter of every ID counted over again.
+pub fn reference_part1( input: &[String] ) -> String {
+    let with = |n: usize| input
+                            .iter()
+                            .filter( |id| id.chars().any( |c| id.chars().filter( |&d| d == c ).count() == n ) )
+                            .count();
+
+    (with( 2 ) * with( 3 )).to_string()
+}
+
+/// Reference: every pair of IDs compared letter by letter.
+pub fn reference_part2( input: &[String] ) -> String {
+    let mut common = vec![];
+
+    for (i, a) in input.iter().enumerate() {
+        for b in &input[ i + 1 .. ] {
+            let (a, b) = (a.chars().collect::<Vec<_>>(), b.chars().collect::<Vec<_>>());
+            if a.len() != b.len() {
+                continue;
+            }
+
+            let differ = (0 .. a.len()).filter( |&k| a[ k ] != b[ k ] ).collect::<Vec<_>>();
+            if differ.len() == 1 {
+                common.push( a.iter().enumerate().filter( |&(k, _)| k != differ[ 0 ] ).map( |(_, c)| c ).collect::<String>() );
+            }
+        }
+    }
+
+    common.join( "\n" )
+}
+
+/// Optimised solutions paired with their references, for `diff-test`.
+pub const CHECKS: [diff::Check; 2] = [
+    ("part1", |input| part1( input.iter().cloned() ).to_string(), reference_part1),
+    ("part2", |input| part2( input.iter().cloned() ), reference_part2)
+];
+
+/// Random IDs where exactly one pair differs by a single letter.
+pub fn generate( rng: &mut Rng, size: usize ) -> Vec<String> {
+    const LENGTH: usize = 26;
+
+    let masks = |id: &[u8]| {
+        (0 .. id.len())
+            .map( |i| {
+                let mut masked = id.to_vec();
+                masked[ i ] = b'_';
+                masked
+            } )
+            .collect::<Vec<_>>()
+    };
+
+    let mut seen = HashSet::<Vec<u8>>::default();
+    let mut ids = vec![];
+
+    while ids.len() < size.max( 2 ) - 1 {
+        let id = (0 .. LENGTH).map( |_| b'a' + rng.below( 26 ) as u8 ).collect::<Vec<_>>();
+        let masked = masks( &id );
+
+        if masked.iter().all( |m| ! seen.contains( m ) ) {
+            seen.extend( masked );
+            ids.push( id );
+        }
+    }
+
+    let twin = loop {
+        let mut twin = rng.pick( &ids ).clone();
+        let at = rng.below( LENGTH );
+        twin[ at ] = b'a' + (twin[ at ] - b'a' + 1 + rng.below( 25 ) as u8) % 26;
+
+        let unique = masks( &twin )
+                        .iter()
+                        .enumerate()
+                        .all( |(i, m)| i == at || ! seen.contains( m ) );
+
+        if unique {
+            break twin;
+        }
+    };
+
+    ids.push( twin );
+    rng.shuffle( &mut ids );
+
+    ids.into_iter().map( |id| String::from_utf8( id ).unwrap() ).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc::gen::Rng;
+    use proptest::prelude::*;
+
+    fn same_length() -> impl Strategy<Value=(String, String)> {
+        let id = |n| prop::collection::vec( prop::char::range( 'a', 'c' ), n ).prop_map( |v| v.into_iter().collect::<String>() );
+        (1 .. 12usize).prop_flat_map( move |n| (id( n ), id( n )) )
+    }
+
+    #[test]
+    fn references_agree() {
+        assert!( diff::check( 50, 100, 0, generate, &CHECKS ).unwrap().is_none() );
+    }
+
+    #[test]
+    fn checksum_reports_any_counts() {
+        let ids = [ "abcdef", "bababc", "abbcde", "abcccd", "aabcdd", "abcdee", "ababab" ].iter().map( |s| s.to_string() ).collect::<Vec<_>>();
+        let checksum = checksum( &ids, &[ 2, 3, 4 ] );
+
+        assert_eq!( checksum.ids, vec![ (2, vec![ 1, 2, 4, 5 ]), (3, vec![ 1, 3, 6 ]), (4, vec![]) ] );
+        assert_eq!( checksum.product, Some( 0 ) );
+
+        let report = checksum.report( &ids );
+        assert!( report.starts_with( "count 2: 4 IDs (bababc abbcde aabcdd abcdee)\ncount 3: 3 IDs (bababc abcccd ababab)\ncount 4: 0 IDs ()\nproduct: 0\n\n" ) );
+        assert!( report.contains( "\nbababc  a=2 b=3 c=1  -> 2, 3\n" ) );
+        assert!( report.ends_with( "\nababab  a=3 b=3  -> 3" ) );
+        assert_eq!( solve_part1( &ids ), 12 );
+    }
+
+    #[test]
+    fn metrics_match_known_distances() {
+        let chars = |s: &str| s.chars().collect::<Vec<_>>();
+
+        assert_eq!( Metric::Levenshtein.distance( &chars( "kitten" ), &chars( "sitting" ) ), 3 );
+        assert_eq!( Metric::Levenshtein.distance( &chars( "" ), &chars( "abc" ) ), 3 );
+        assert_eq!( Metric::Hamming.distance( &chars( "karolin" ), &chars( "kathrin" ) ), 3 );
+        assert_eq!( Metric::Hamming.distance( &chars( "abc" ), &chars( "ab" ) ), usize::MAX );
+        assert_eq!( "levenshtein".parse(), Ok( Metric::Levenshtein ) );
+        assert!( "euclid".parse::<Metric>().is_err() );
+    }
+
+    #[test]
+    fn clusters_chain_close_ids() {
+        let ids = [ "abcd", "abce", "xyz", "abfe", "xyw", "qqqq", "abc" ].iter().map( |s| s.to_string() ).collect::<Vec<_>>();
+
+        assert_eq!( clusters( &ids, Metric::Hamming, 1 ), vec![ vec![ 0, 1, 3 ], vec![ 2, 4 ] ] );
+        assert_eq!( clusters( &ids, Metric::Levenshtein, 1 ), vec![ vec![ 0, 1, 3, 6 ], vec![ 2, 4 ] ] );
+        assert!( clusters( &ids, Metric::Hamming, 0 ).is_empty() );
+    }
+
+    proptest! {
+        #[test]
+        fn clusters_agree_with_comparing_all_pairs( ids in prop::collection::vec( "[a-c]{0,5}", 0 .. 30 ), k in 0 .. 3usize, levenshtein: bool ) {
+            let metric = if levenshtein { Metric::Levenshtein } else { Metric::Hamming };
+            let chars = ids.iter().map( |id| id.chars().collect::<Vec<_>>() ).collect::<Vec<_>>();
+
+            // NOTE: Merging whole groups until nothing changes
+            let mut groups = (0 .. ids.len()).map( |i| vec![ i ] ).collect::<Vec<_>>();
+            let mut merged = true;
+            while merged {
+                merged = false;
+
+                'search: for a in 0 .. groups.len() {
+                    for b in a + 1 .. groups.len() {
+                        let close = groups[ a ].iter().any( |&i| groups[ b ].iter().any( |&j| metric.distance( &chars[ i ], &chars[ j ] ) <= k ) );
+
+                        if close {
+                            let group = groups.remove( b );
+                            groups[ a ].extend( group );
+                            merged = true;
+                            break 'search;
+                        }
+                    }
+                }
+            }
+
+            let mut expected = groups.into_iter().filter( |g| g.len() > 1 ).map( |mut g| { g.sort(); g } ).collect::<Vec<_>>();
+            expected.sort();
+
+            prop_assert_eq!( clusters( &ids, metric, k ), expected );
+        }
+
+        #[test]
+        fn differ_by_one_finds_single_mismatch( (a, b) in same_length() ) {
+            let mismatches = a.chars().zip( b.chars() ).enumerate().filter( |(_, (x, y))| x != y ).collect::<Vec<_>>();
+            let expected = if mismatches.len() == 1 { Some( mismatches[ 0 ].0 ) } else { None };
+
+            prop_assert_eq!( differ_by_one( &a, &b ), expected );
+            prop_assert_eq!( differ_by_one( &b, &a ), expected );
+        }
+
+        #[test]
+        fn part1_counts_doubles_and_triples( ids in prop::collection::vec( "[a-c]{0,8}", 0 .. 20 ) ) {
+            let has = |id: &str, n: usize| id.chars().any( |c| id.matches( c ).count() == n );
+            let twos = ids.iter().filter( |id| has( id, 2 ) ).count();
+            let threes = ids.iter().filter( |id| has( id, 3 ) ).count();
+
+            prop_assert_eq!( part1( ids.into_iter() ).to_string(), (twos * threes).to_string() );
+        }
+
+        #[test]
+        fn close_pairs_are_every_pair_differing_by_one( ids in prop::collection::vec( "[a-c]{1,4}", 0 .. 40 ) ) {
+            let mut expected = vec![];
+            for j in 0 .. ids.len() {
+                for i in 0 .. j {
+                    if ids[ i ].len() == ids[ j ].len() {
+                        if let Some( k ) = differ_by_one( &ids[ i ], &ids[ j ] ) {
+                            expected.push( (i, j, k) );
+                        }
+                    }
+                }
+            }
+
+            expected.sort_unstable();
+            prop_assert_eq!( close_pairs( &ids ), expected );
+        }
+
+        #[test]
+        fn part2_drops_the_differing_letter( id in "[a-m]{2,12}", k in any::<prop::sample::Index>(), fillers in 0 .. 10usize ) {
+            let k = k.index( id.len() );
+            let twin = id.chars().enumerate().map( |(i, c)| if i == k { 'z' } else { c } ).collect::<String>();
+
+            // NOTE: Fillers differ from everything else on every position
+            let mut ids = (0 .. fillers)
+                            .map( |i| ((b'n' + i as u8) as char).to_string().repeat( id.len() ) )
+                            .collect::<Vec<_>>();
+            ids.insert( fillers / 2, twin );
+            ids.push( id.clone() );
+
+            let common = id.chars().enumerate().filter( |&(i, _)| i != k ).map( |(_, c)| c ).collect::<String>();
+            prop_assert_eq!( part2( ids.into_iter() ).to_string(), common );
+        }
+
+        #[test]
+        fn generated_input_has_one_close_pair( seed: u64, size in 2 .. 100usize ) {
+            let input = generate( &mut Rng::new( seed ), size );
+            prop_assert_eq!( input.len(), size );
+
+            let close = (0 .. size)
+                            .flat_map( |j| (0 .. j).map( move |i| (i, j) ) )
+                            .filter( |&(i, j)| differ_by_one( &input[ i ], &input[ j ] ).is_some() || input[ i ] == input[ j ] )
+                            .count();
+
+            prop_assert_eq!( close, 1 );
+            prop_assert_eq!( part2( input.into_iter() ).to_string().len(), 25 );
+        }
+    }
+}
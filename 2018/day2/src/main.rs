@@ -1,90 +1,79 @@
 use std::env;
-use std::fmt::Display;
 use std::fs;
 use std::io::{BufRead, BufReader};
-use std::collections::{HashMap, HashSet};
 
-fn part1<I: Iterator<Item=String>>( input: I ) -> impl Display {
-    let mut two_times = 0;
-    let mut three_times = 0;
+use aoc::config::Config;
+use aoc::diff;
+use aoc::gen;
+use aoc::mem;
+use day2::{checksum, clusters, generate, parse, part1, part2, solve_part1, solve_part2, Metric, CHECKS, DISTANCE, METRIC};
 
-    for id in input {
-        let local = id.chars().fold( HashMap::<char, usize>::default(), |mut a, e| {
-            *a.entry( e ).or_insert( 0 ) += 1;
-            a
-        } ).values().cloned().collect::<HashSet<_>>();
+#[global_allocator]
+static ALLOCATOR: mem::Counting = mem::Counting;
 
-        if local.contains( &2 ) {
-            two_times += 1;
-        }
-
-        if local.contains( &3 ) {
-            three_times += 1;
-        }
-    }
-
-    two_times * three_times
+fn usage() -> &'static str {
+    "usage: cargo run --release -- <part1|part2|clusters> [input] | checksum [input] [--counts 2,3,4] | gen [size] [seed] | diff-test [cases] [size] [seed] | --profile-mem [input]"
 }
 
-fn differ_by_one( a: &str, b: &str ) -> Option<usize> {
-    let mut found = None;
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let config = Config::load( env!( "CARGO_MANIFEST_DIR" ) )?;
+    config.watch();
 
-    for (i, (x, y)) in a.chars().zip( b.chars() ).enumerate() {
-        if x != y {
-            if found.is_none() {
-                found = Some( i );
-            } else {
-                return None;
-            }
-        }
+    let part = env::args().nth( 1 ).unwrap_or_else( || config.part() );
+    if part == "--profile-mem" {
+        let input_file = env::args().nth( 2 ).unwrap_or_else( || config.input() );
+        return mem::profile( &input_file, parse, |ids| solve_part1( ids ), |ids| solve_part2( ids ) );
     }
 
-    found
-}
-
-fn part2<I: Iterator<Item=String>>( input: I ) -> impl Display {
-    // NOTE: Dataset is so small that O(n^2) solution is good enough
-
-    let strings = input.collect::<Vec<_>>();
-
-    for j in 0..strings.len() {
-        for i in 0..strings.len() {
-            if i == j { continue; }
-
-            let diff = differ_by_one( & strings[ j ], & strings[ i ] );
-            if let Some( k ) = diff {
-                return strings[ i ]
-                            .chars()
-                            .enumerate()
-                            .filter_map( |(i, c)| if i != k { Some( c ) } else { None } )
-                            .collect::<String>();
-            }
-        }
+    match part.as_ref() {
+        "part1" | "part2" | "clusters" | "checksum" => {},
+        "gen"                                       => return gen::run( 250, generate ),
+        "diff-test"                                 => return diff::run( 250, generate, &CHECKS ),
+        _                                           => return Err( usage().into() )
     }
 
-    // NOTE: Problem states that there exists exactly one such pair
-    unreachable!()
-}
-
-fn usage() -> &'static str {
-    "usage: cargo run --release -- <part1|part2>"
-}
-
-fn main() -> Result<(), Box<std::error::Error>> {
-    let part = env::args().nth( 1 ).unwrap_or_else( || "part1".into() );
-    let first = match part.as_ref() {
-        "part1"     => true,
-        "part2"     => false,
-        _           => return Err( usage().into() )
+    // NOTE: `--counts` may come before or after the input
+    let mut args = env::args().skip( 2 ).collect::<Vec<_>>();
+    let counts = match args.iter().position( |a| a == "--counts" ) {
+        Some( i ) if part == "checksum" => {
+            let list = args.get( i + 1 ).ok_or( usage() )?.clone();
+            args.drain( i ..= i + 1 );
+
+            list.split( ',' ).map( |n| n.trim().parse::<usize>() ).collect::<Result<Vec<_>, _>>()?
+        },
+        Some( _ )                       => return Err( usage().into() ),
+        None                            => vec![ 2, 3 ]
     };
 
-    let input_file = env::args().nth( 2 ).unwrap_or_else( || "res/input".into() );
+    let input_file = args.into_iter().next().unwrap_or_else( || config.input() );
 
     let reader = BufReader::new( fs::File::open( input_file )? );
     let mut lines = reader.lines().map( |l| l.expect( "invalid input" ) );
 
-    if first { println!( "{}", part1( &mut lines ) ) }
-    else     { println!( "{}", part2( &mut lines ) ) };
+    match part.as_ref() {
+        "part1" => config.print( "part1", part1( &mut lines ) ),
+        "part2" => {
+            let common = part2( &mut lines );
+            if common.is_empty() {
+                return Err( "no two IDs differ by a single letter".into() );
+            }
+
+            config.print( "part2", common )
+        },
+        "checksum" => {
+            let ids = parse( &mut lines );
+            println!( "{}", checksum( &ids, &counts ).report( &ids ) );
+        },
+        _       => {
+            let metric = config.param( "metric", METRIC.to_string() )?.parse::<Metric>()?;
+            let distance = config.param( "distance", DISTANCE )?;
+            let ids = parse( &mut lines );
+
+            for cluster in clusters( &ids, metric, distance ) {
+                println!( "{}", cluster.iter().map( |&i| ids[ i ].as_str() ).collect::<Vec<_>>().join( " " ) );
+            }
+        }
+    }
 
 
     Ok( () )
@@ -0,0 +1,1042 @@
+//! Fabric claims, e.g. `#123 @ 3,2: 5x4`, and how they overlap.
+
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, BinaryHeap, HashMap};
+
+use aoc::diff;
+use aoc::export::{self, Image};
+use aoc::gen::Rng;
+use aoc::repl;
+
+/// What's wrong with the text at some column of a claim.
+#[derive(Debug, PartialEq)]
+pub enum Problem {
+    /// Something else is there, or nothing.
+    Expected,
+    /// A number which doesn't fit into `usize`.
+    TooBig,
+    /// A size reaching past the largest coordinate.
+    TooFar
+}
+
+/// Why a line isn't a valid claim, and where.
+#[derive(Debug, PartialEq)]
+pub struct InvalidClaim {
+    /// Part of the claim at fault, e.g. `width` or `' @ '`.
+    pub field: &'static str,
+    /// Column it starts at, counting from one.
+    pub column: usize,
+    /// Text found there, empty at the end of the line.
+    pub found: String,
+    pub problem: Problem
+}
+
+impl std::fmt::Display for InvalidClaim {
+    fn fmt( &self, f: &mut std::fmt::Formatter ) -> std::fmt::Result {
+        let found = if self.found.is_empty() { "end of line".to_string() } else { format!( "'{}'", self.found ) };
+
+        match self.problem {
+            Problem::Expected   => write!( f, "invalid claim: expected {} at column {}, found {}", self.field, self.column, found ),
+            Problem::TooBig     => write!( f, "invalid claim: {} at column {} is too big: {}", self.field, self.column, found ),
+            Problem::TooFar     => write!( f, "invalid claim: {} at column {} reaches too far: {}", self.field, self.column, found )
+        }
+    }
+}
+
+impl std::error::Error for InvalidClaim {}
+
+/// Rectangle of fabric claimed by an elf, `position` counts inches from the
+/// top left edge.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Claim {
+    pub id: usize,
+    pub position: (usize, usize),
+    pub size: (usize, usize)
+}
+
+/// Reads a claim left to right, remembering where it is for errors.
+struct Cursor<'a> {
+    line: &'a str,
+    at: usize
+}
+
+impl<'a> Cursor<'a> {
+    fn error( &self, field: &'static str, found: &str, problem: Problem ) -> InvalidClaim {
+        InvalidClaim { field, column: self.line[ .. self.at ].chars().count() + 1, found: found.to_string(), problem }
+    }
+
+    fn literal( &mut self, text: &'static str, field: &'static str ) -> Result<(), InvalidClaim> {
+        let rest = &self.line[ self.at .. ];
+        if ! rest.starts_with( text ) {
+            let found = rest.char_indices().nth( text.chars().count() ).map_or( rest, |(i, _)| &rest[ .. i ] );
+            return Err( self.error( field, found, Problem::Expected ) );
+        }
+
+        self.at += text.len();
+        Ok( () )
+    }
+
+    fn number( &mut self, field: &'static str ) -> Result<usize, InvalidClaim> {
+        let rest = &self.line[ self.at .. ];
+        let digits = &rest[ .. rest.find( |c: char| ! c.is_ascii_digit() ).unwrap_or( rest.len() ) ];
+
+        if digits.is_empty() {
+            return Err( self.error( field, rest.chars().next().map_or( "", |c| &rest[ .. c.len_utf8() ] ), Problem::Expected ) );
+        }
+
+        let number = digits.parse().map_err( |_| self.error( field, digits, Problem::TooBig ) )?;
+        self.at += digits.len();
+
+        Ok( number )
+    }
+
+    fn end( &self ) -> Result<(), InvalidClaim> {
+        match &self.line[ self.at .. ] {
+            ""      => Ok( () ),
+            rest    => Err( self.error( "end of line", rest, Problem::Expected ) )
+        }
+    }
+}
+
+/// Exactly `#id @ x,y: wxh`, all numbers being plain digits.
+impl std::str::FromStr for Claim {
+    type Err = InvalidClaim;
+
+    fn from_str( s: &str ) -> Result<Self, Self::Err> {
+        let mut cursor = Cursor { line: s, at: 0 };
+
+        cursor.literal( "#", "'#'" )?;
+        let id = cursor.number( "id" )?;
+        cursor.literal( " @ ", "' @ '" )?;
+        let x = cursor.number( "x" )?;
+        cursor.literal( ",", "','" )?;
+        let y = cursor.number( "y" )?;
+        cursor.literal( ": ", "': '" )?;
+
+        let width_at = cursor.at;
+        let w = cursor.number( "width" )?;
+        cursor.literal( "x", "'x'" )?;
+
+        let height_at = cursor.at;
+        let h = cursor.number( "height" )?;
+        cursor.end()?;
+
+        // NOTE: The far corner has to fit too, see `corners`
+        if x.checked_add( w ).is_none() {
+            return Err( Cursor { line: s, at: width_at }.error( "width", &w.to_string(), Problem::TooFar ) );
+        }
+
+        if y.checked_add( h ).is_none() {
+            return Err( Cursor { line: s, at: height_at }.error( "height", &h.to_string(), Problem::TooFar ) );
+        }
+
+        Ok( Claim { id, position: (x, y), size: (w, h) } )
+    }
+}
+
+impl std::fmt::Display for Claim {
+    fn fmt( &self, f: &mut std::fmt::Formatter ) -> std::fmt::Result {
+        write!( f, "#{} @ {},{}: {}x{}", self.id, self.position.0, self.position.1, self.size.0, self.size.1 )
+    }
+}
+
+/// Claims, one per line, each with its own ID.
+pub fn parse<I: Iterator<Item=String>>( input: I ) -> Result<Vec<Claim>, String> {
+    let mut lines = HashMap::new();
+    let mut claims = vec![];
+
+    for (i, line) in input.enumerate() {
+        let claim = line.parse::<Claim>().map_err( |e| format!( "line {}: {}", i + 1, e ) )?;
+
+        if let Some( first ) = lines.insert( claim.id, i + 1 ) {
+            return Err( format!( "line {}: claim #{} is already on line {}", i + 1, claim.id, first ) );
+        }
+
+        claims.push( claim );
+    }
+
+    Ok( claims )
+}
+
+/// Top left corner of the claim and the one just past its bottom right.
+// NOTE: Parsing makes sure the far corner fits
+fn corners( claim: &Claim ) -> ((usize, usize), (usize, usize)) {
+    (claim.position, (claim.position.0 + claim.size.0, claim.position.1 + claim.size.1))
+}
+
+// Claims without a single square inch can't overlap anything, not even
+// when they lie within another claim.
+fn has_area( claim: &Claim ) -> bool {
+    claim.size.0 > 0 && claim.size.1 > 0
+}
+
+/// Segment tree over the gaps between sorted coordinates, which knows how
+/// much of the line is covered at least once and at least twice by the
+/// intervals added to it.
+// NOTE: An interval covering a whole node is only counted in that node
+struct Coverage {
+    coordinates: Vec<usize>,
+    cover: Vec<u32>,
+    at_least: Vec<[u128; 2]>
+}
+
+impl Coverage {
+    fn new( coordinates: Vec<usize> ) -> Self {
+        let nodes = 4 * coordinates.len().max( 1 );
+        Coverage { coordinates, cover: vec![ 0; nodes ], at_least: vec![ [0; 2]; nodes ] }
+    }
+
+    /// Adds (or takes away) the interval between the `from`th and the `to`th
+    /// coordinate.
+    fn add( &mut self, from: usize, to: usize, delta: i32 ) {
+        if from < to {
+            self.update( 1, 0, self.coordinates.len() - 1, from, to, delta );
+        }
+    }
+
+    fn update( &mut self, node: usize, lo: usize, hi: usize, from: usize, to: usize, delta: i32 ) {
+        if to <= lo || hi <= from {
+            return;
+        }
+
+        if from <= lo && hi <= to {
+            self.cover[ node ] = self.cover[ node ].wrapping_add_signed( delta );
+        } else {
+            let mid = (lo + hi) / 2;
+            self.update( 2 * node, lo, mid, from, to, delta );
+            self.update( 2 * node + 1, mid, hi, from, to, delta );
+        }
+
+        let full = (self.coordinates[ hi ] - self.coordinates[ lo ]) as u128;
+        for times in 1 ..= 2 {
+            let cover = self.cover[ node ] as usize;
+
+            self.at_least[ node ][ times - 1 ] = if cover >= times {
+                full
+            } else if hi - lo == 1 {
+                0
+            } else {
+                self.at_least[ 2 * node ][ times - cover - 1 ] + self.at_least[ 2 * node + 1 ][ times - cover - 1 ]
+            };
+        }
+    }
+
+    fn twice( &self ) -> u128 {
+        self.at_least[ 1 ][ 1 ]
+    }
+}
+
+/// Square inches of fabric within two or more claims.
+// NOTE: A line sweeps the fabric from left to right, keeping track of which
+// claims it crosses, so the area only depends on the number of claims and
+// not on how big the fabric is
+pub fn solve_part1( claims: &[Claim] ) -> u128 {
+    let mut ys = claims.iter().flat_map( |c| { let ((_, y0), (_, y1)) = corners( c ); [ y0, y1 ] } ).collect::<Vec<_>>();
+    ys.sort_unstable();
+    ys.dedup();
+
+    let index = |y: usize| ys.binary_search( &y ).unwrap();
+    let mut events = claims
+                        .iter()
+                        .flat_map( |c| {
+                            let ((x0, y0), (x1, y1)) = corners( c );
+                            [ (x0, 1, index( y0 ), index( y1 )), (x1, -1, index( y0 ), index( y1 )) ]
+                        } )
+                        .collect::<Vec<_>>();
+
+    events.sort_unstable();
+
+    let mut coverage = Coverage::new( ys.clone() );
+    let mut area = 0;
+
+    for (i, &(x, delta, from, to)) in events.iter().enumerate() {
+        coverage.add( from, to, delta );
+
+        if let Some( &(next, ..) ) = events.get( i + 1 ) {
+            area += (next - x) as u128 * coverage.twice();
+        }
+    }
+
+    area
+}
+
+/// Segment tree over the gaps between sorted coordinates, holding claims by
+/// the gaps they span so the ones over a given gap are quick to find.
+// NOTE: Claims which ended are only dropped from the nodes a lookup walks
+// through, so each one is dropped at most once per node it's in
+struct Spans {
+    gaps: usize,
+    claims: Vec<Vec<usize>>
+}
+
+impl Spans {
+    fn new( coordinates: usize ) -> Self {
+        Spans { gaps: coordinates.saturating_sub( 1 ), claims: vec![ vec![]; 4 * coordinates.max( 1 ) ] }
+    }
+
+    /// Adds a claim spanning from the `from`th to the `to`th coordinate.
+    fn insert( &mut self, from: usize, to: usize, claim: usize ) {
+        if from < to {
+            self.add( 1, 0, self.gaps, from, to, claim );
+        }
+    }
+
+    fn add( &mut self, node: usize, lo: usize, hi: usize, from: usize, to: usize, claim: usize ) {
+        if to <= lo || hi <= from {
+            return;
+        }
+
+        if from <= lo && hi <= to {
+            self.claims[ node ].push( claim );
+        } else {
+            let mid = (lo + hi) / 2;
+            self.add( 2 * node, lo, mid, from, to, claim );
+            self.add( 2 * node + 1, mid, hi, from, to, claim );
+        }
+    }
+
+    /// Claims still `active` spanning the `gap`th gap.
+    fn over( &mut self, gap: usize, active: &[bool] ) -> Vec<usize> {
+        let (mut node, mut lo, mut hi) = (1, 0, self.gaps);
+        let mut found = vec![];
+
+        loop {
+            self.claims[ node ].retain( |&c| active[ c ] );
+            found.extend( &self.claims[ node ] );
+
+            if hi - lo <= 1 {
+                return found;
+            }
+
+            let mid = (lo + hi) / 2;
+            if gap < mid {
+                (node, hi) = (2 * node, mid);
+            } else {
+                (node, lo) = (2 * node + 1, mid);
+            }
+        }
+    }
+}
+
+/// Indices of every two claims sharing at least one square inch, the
+/// smaller index first.
+// NOTE: Claims are visited from left to right and only compared with the
+// ones the line still crosses. Two of them overlap on y when one starts
+// within the other, so those starting within the new claim come from a set
+// ordered by their top and those it starts within from a segment tree
+pub fn overlapping_pairs( claims: &[Claim] ) -> Vec<(usize, usize)> {
+    let mut order = (0 .. claims.len()).filter( |&i| has_area( &claims[ i ] ) ).collect::<Vec<_>>();
+    order.sort_unstable_by_key( |&i| claims[ i ].position.0 );
+
+    let mut ys = order.iter().flat_map( |&i| { let ((_, y0), (_, y1)) = corners( &claims[ i ] ); [ y0, y1 ] } ).collect::<Vec<_>>();
+    ys.sort_unstable();
+    ys.dedup();
+
+    let index = |y: usize| ys.binary_search( &y ).unwrap();
+    let mut active = vec![ false; claims.len() ];
+    let mut ending = BinaryHeap::<Reverse<(usize, usize)>>::new();
+    let mut tops = BTreeSet::new();
+    let mut spans = Spans::new( ys.len() );
+    let mut pairs = vec![];
+
+    for i in order {
+        let ((x0, y0), (x1, y1)) = corners( &claims[ i ] );
+
+        while let Some( &Reverse( (end, a) ) ) = ending.peek() {
+            if end > x0 {
+                break;
+            }
+
+            ending.pop();
+            active[ a ] = false;
+            tops.remove( &(claims[ a ].position.1, a) );
+        }
+
+        let starting_within = tops.range( (y0, 0) .. (y1, 0) ).map( |&(_, a)| a );
+        let started_before = spans.over( index( y0 ), &active ).into_iter().filter( |&a| claims[ a ].position.1 < y0 );
+
+        for a in starting_within.chain( started_before ).collect::<Vec<_>>() {
+            pairs.push( (a.min( i ), a.max( i )) );
+        }
+
+        active[ i ] = true;
+        ending.push( Reverse( (x1, i) ) );
+        tops.insert( (y0, i) );
+        spans.insert( index( y0 ), index( y1 ), i );
+    }
+
+    pairs.sort_unstable();
+    pairs
+}
+
+/// Square inches two claims have in common.
+pub fn shared_area( a: &Claim, b: &Claim ) -> u128 {
+    let ((ax0, ay0), (ax1, ay1)) = corners( a );
+    let ((bx0, by0), (bx1, by1)) = corners( b );
+
+    let width = ax1.min( bx1 ).saturating_sub( ax0.max( bx0 ) );
+    let height = ay1.min( by1 ).saturating_sub( ay0.max( by0 ) );
+
+    width as u128 * height as u128
+}
+
+/// Claims together with whom each of them overlaps, and by how much.
+pub struct Overlaps {
+    pub claims: Vec<Claim>,
+    /// Indices of the claims overlapping the claim at the same index, with
+    /// the area they share, in input order.
+    pub conflicts: Vec<Vec<(usize, u128)>>,
+    by_id: HashMap<usize, usize>
+}
+
+impl Overlaps {
+    pub fn new( claims: Vec<Claim> ) -> Self {
+        let mut conflicts = vec![ vec![]; claims.len() ];
+
+        for (a, b) in overlapping_pairs( &claims ) {
+            let area = shared_area( &claims[ a ], &claims[ b ] );
+            conflicts[ a ].push( (b, area) );
+            conflicts[ b ].push( (a, area) );
+        }
+
+        for list in &mut conflicts {
+            list.sort_unstable();
+        }
+
+        // NOTE: `parse` makes sure IDs don't repeat, otherwise the first claim wins
+        let mut by_id = HashMap::new();
+        for (i, claim) in claims.iter().enumerate() {
+            by_id.entry( claim.id ).or_insert( i );
+        }
+
+        Overlaps { claims, conflicts, by_id }
+    }
+
+    /// Index of the claim with the given ID.
+    pub fn find( &self, id: usize ) -> Option<usize> {
+        self.by_id.get( &id ).copied()
+    }
+
+    /// Indices of the claims overlapping nothing, in input order.
+    pub fn intact( &self ) -> Vec<usize> {
+        (0 .. self.claims.len()).filter( |&i| self.conflicts[ i ].is_empty() ).collect()
+    }
+
+    /// Groups of claims linked by overlaps, each of two claims or more and
+    /// sorted, ordered by their first claim.
+    pub fn components( &self ) -> Vec<Vec<usize>> {
+        let mut seen = vec![ false; self.claims.len() ];
+        let mut components = vec![];
+
+        for start in 0 .. self.claims.len() {
+            if seen[ start ] || self.conflicts[ start ].is_empty() {
+                continue;
+            }
+
+            seen[ start ] = true;
+            let mut component = vec![ start ];
+            let mut next = 0;
+
+            while let Some( &i ) = component.get( next ) {
+                for &(j, _) in &self.conflicts[ i ] {
+                    if ! seen[ j ] {
+                        seen[ j ] = true;
+                        component.push( j );
+                    }
+                }
+
+                next += 1;
+            }
+
+            component.sort_unstable();
+            components.push( component );
+        }
+
+        components
+    }
+}
+
+/// ID of the claim that doesn't overlap any other, the first one in the
+/// input if there are several, `None` if every claim overlaps another.
+pub fn solve_part2( claims: &[Claim] ) -> Option<usize> {
+    let mut intact = vec![ true; claims.len() ];
+    for (a, b) in overlapping_pairs( claims ) {
+        intact[ a ] = false;
+        intact[ b ] = false;
+    }
+
+    claims.iter().zip( intact ).find( |(_, intact)| *intact ).map( |(c, _)| c.id )
+}
+
+fn claim_arg( overlaps: &Overlaps, args: &[&str] ) -> Result<usize, String> {
+    let id = repl::arg::<String>( args, 0, "id" )?;
+    id.trim_start_matches( '#' )
+        .parse()
+        .ok()
+        .and_then( |id| overlaps.find( id ) )
+        .ok_or_else( || format!( "no claim '{}'", id ) )
+}
+
+fn ids( overlaps: &Overlaps, claims: &[usize] ) -> String {
+    if claims.is_empty() {
+        return "none".into();
+    }
+
+    claims.iter().map( |&i| format!( "#{}", overlaps.claims[ i ].id ) ).collect::<Vec<_>>().join( " " )
+}
+
+pub const COMMANDS: [repl::Command<Overlaps>; 4] = [
+    repl::Command {
+        name: "intact",
+        args: "",
+        help: "claims which overlap no other claim",
+        run: |overlaps, _| Ok( ids( overlaps, &overlaps.intact() ) )
+    },
+    repl::Command {
+        name: "conflicts",
+        args: "<id>",
+        help: "claims overlapping claim <id> and the square inches they share",
+        run: |overlaps, args| {
+            let claim = claim_arg( overlaps, args )?;
+            if overlaps.conflicts[ claim ].is_empty() {
+                return Ok( "none".into() );
+            }
+
+            Ok( overlaps.conflicts[ claim ]
+                    .iter()
+                    .map( |&(i, area)| format!( "#{} shares {}", overlaps.claims[ i ].id, area ) )
+                    .collect::<Vec<_>>()
+                    .join( "\n" ) )
+        }
+    },
+    repl::Command {
+        name: "component",
+        args: "<id>",
+        help: "claims linked to claim <id> through overlaps",
+        run: |overlaps, args| {
+            let claim = claim_arg( overlaps, args )?;
+            let component = overlaps.components().into_iter().find( |c| c.contains( &claim ) ).unwrap_or_else( || vec![ claim ] );
+
+            Ok( ids( overlaps, &component ) )
+        }
+    },
+    repl::Command {
+        name: "components",
+        args: "",
+        help: "every group of claims linked through overlaps, one per line",
+        run: |overlaps, _| {
+            let components = overlaps.components();
+            if components.is_empty() {
+                return Ok( "none".into() );
+            }
+
+            Ok( components.iter().map( |c| ids( overlaps, c ) ).collect::<Vec<_>>().join( "\n" ) )
+        }
+    }
+];
+
+/// `solve_part1` straight from the input lines.
+pub fn part1<I: Iterator<Item=String>>( input: I ) -> Result<u128, String> {
+    Ok( solve_part1( &parse( input )? ) )
+}
+
+/// `solve_part2` straight from the input lines.
+pub fn part2<I: Iterator<Item=String>>( input: I ) -> Result<usize, String> {
+    solve_part2( &parse( input )? ).ok_or_else( || "no claim is intact".to_string() )
+}
+
+// Number of claims over every square inch, row by row, as wide as the
+// farthest claim.
+fn reference_fabric( claims: &[Claim] ) -> (usize, Vec<u32>) {
+    let width = claims.iter().map( |c| c.position.0 + c.size.0 ).max().unwrap_or( 0 );
+    let height = claims.iter().map( |c| c.position.1 + c.size.1 ).max().unwrap_or( 0 );
+    let mut fabric = vec![ 0; width * height ];
+
+    for c in claims {
+        for y in c.position.1 .. c.position.1 + c.size.1 {
+            for x in c.position.0 .. c.position.0 + c.size.0 {
+                fabric[ y * width + x ] += 1;
+            }
+        }
+    }
+
+    (width, fabric)
+}
+
+/// Reference: every square inch of the fabric counted one by one.
+pub fn reference_part1( input: &[String] ) -> String {
+    let claims = parse( input.iter().cloned() ).expect( "invalid claims" );
+    reference_fabric( &claims ).1.iter().filter( |&&n| n >= 2 ).count().to_string()
+}
+
+/// Reference: the first claim whose square inches are all its own.
+pub fn reference_part2( input: &[String] ) -> String {
+    let claims = parse( input.iter().cloned() ).expect( "invalid claims" );
+    let (width, fabric) = reference_fabric( &claims );
+
+    claims
+        .iter()
+        .find( |c| {
+            (c.position.1 .. c.position.1 + c.size.1)
+                .all( |y| (c.position.0 .. c.position.0 + c.size.0).all( |x| fabric[ y * width + x ] == 1 ) )
+        } )
+        .map_or( "no claim is intact".into(), |c| c.id.to_string() )
+}
+
+/// Optimised solutions paired with their references, for `diff-test`.
+pub const CHECKS: [diff::Check; 2] = [
+    ("part1", |input| part1( input.iter().cloned() ).map_or_else( |e| e, |area| area.to_string() ), reference_part1),
+    ("part2", |input| part2( input.iter().cloned() ).map_or_else( |e| e, |id| id.to_string() ), reference_part2)
+];
+
+/// Area covered by exactly `k` claims for every `k`, and where the claims
+/// pile up the most.
+pub struct Depths {
+    /// Square inches under `k` claims at index `k`, the first one being the
+    /// unclaimed fabric up to the farthest claim.
+    pub area: Vec<u128>,
+    /// Top left square of the deepest spot, the leftmost and then topmost
+    /// one if there are several.
+    pub deepest: Option<(usize, usize)>
+}
+
+/// Sweeps the fabric like `solve_part1`, but keeps the depth of every strip
+/// between claim edges separately.
+// NOTE: A claim only touches the strips it spans, which are few as long as
+// claims aren't much taller than the gaps between their edges
+pub fn depths( claims: &[Claim] ) -> Depths {
+    let claims = claims.iter().filter( |c| has_area( c ) ).collect::<Vec<_>>();
+
+    let mut ys = claims.iter().flat_map( |&c| { let ((_, y0), (_, y1)) = corners( c ); [ y0, y1 ] } ).collect::<Vec<_>>();
+    ys.sort_unstable();
+    ys.dedup();
+
+    let index = |y: usize| ys.binary_search( &y ).unwrap();
+    let mut events = claims
+                        .iter()
+                        .flat_map( |&c| {
+                            let ((x0, y0), (x1, y1)) = corners( c );
+                            [ (x0, 1, index( y0 ), index( y1 )), (x1, -1, index( y0 ), index( y1 )) ]
+                        } )
+                        .collect::<Vec<_>>();
+
+    // NOTE: Claims ending at `x` go before those starting there, so depths
+    // never drop below zero and only grow while at the same `x`
+    events.sort_unstable();
+
+    let mut depth = vec![ 0; ys.len() ];
+    let mut since = vec![ 0; ys.len() ];
+    let mut area = vec![ 0u128 ];
+    let mut deepest = None;
+
+    for (x, delta, from, to) in events {
+        for i in from .. to {
+            area[ depth[ i ] ] += (x - since[ i ]) as u128 * (ys[ i + 1 ] - ys[ i ]) as u128;
+            since[ i ] = x;
+
+            if delta < 0 {
+                depth[ i ] -= 1;
+                continue;
+            }
+
+            depth[ i ] += 1;
+            if depth[ i ] == area.len() {
+                area.push( 0 );
+                deepest = Some( (x, ys[ i ]) );
+            } else if depth[ i ] + 1 == area.len() && Some( (x, ys[ i ]) ) < deepest {
+                deepest = Some( (x, ys[ i ]) );
+            }
+        }
+    }
+
+    // NOTE: Strips at depth zero were counted up to the farthest claim only
+    let width = claims.iter().map( |&c| corners( c ).1.0 ).max().unwrap_or( 0 ) as u128;
+    let height = ys.last().copied().unwrap_or( 0 ) as u128;
+    area[ 0 ] = width * height - area[ 1 .. ].iter().sum::<u128>();
+
+    Depths { area, deepest }
+}
+
+impl Depths {
+    pub fn max_depth( &self ) -> usize {
+        self.area.len() - 1
+    }
+
+    /// Area at every depth and the claims over the deepest spot.
+    pub fn report( &self, claims: &[Claim] ) -> String {
+        let width = self.max_depth().to_string().len();
+        let mut lines = self.area
+                            .iter()
+                            .enumerate()
+                            .map( |(k, area)| format!( "depth {:>width$}: {} square inches", k, area, width = width ) )
+                            .collect::<Vec<_>>();
+
+        if let Some( (x, y) ) = self.deepest {
+            let over = claims
+                        .iter()
+                        .filter( |c| overlaps( c, &Claim { id: 0, position: (x, y), size: (1, 1) } ) )
+                        .map( |c| format!( "#{}", c.id ) )
+                        .collect::<Vec<_>>();
+
+            lines.push( format!( "deepest: {} claims at {},{} ({})", self.max_depth(), x, y, over.join( " " ) ) );
+        }
+
+        lines.join( "\n" )
+    }
+}
+
+/// Largest fabric `export` and `heatmap` draw, in square inches.
+pub const EXPORT_LIMIT: usize = 4096 * 4096;
+
+/// Number of claims over every square inch of the fabric, up to the farthest
+/// claim, with its width and height.
+fn fabric<I: Iterator<Item=String>>( input: I ) -> Result<(Vec<usize>, usize, usize), Box<dyn std::error::Error>> {
+    let claims = parse( input )?;
+    let width = claims.iter().map( |c| corners( c ).1.0 ).max().unwrap_or( 0 ).max( 1 );
+    let height = claims.iter().map( |c| corners( c ).1.1 ).max().unwrap_or( 0 ).max( 1 );
+
+    if width.saturating_mul( height ) > EXPORT_LIMIT {
+        return Err( format!( "fabric of {}x{} is too big to export", width, height ).into() );
+    }
+
+    let mut fabric = vec![ 0; width * height ];
+
+    for claim in &claims {
+        for y in claim.position.1..claim.position.1 + claim.size.1 {
+            for x in claim.position.0..claim.position.0 + claim.size.0 {
+                fabric[ y * width + x ] += 1;
+            }
+        }
+    }
+
+    Ok( (fabric, width, height) )
+}
+
+/// Saves the fabric as a PNG image, colored by the number of claims.
+pub fn export<I: Iterator<Item=String>>( input: I, output: &str ) -> Result<(), Box<dyn std::error::Error>> {
+    let (fabric, width, height) = fabric( input )?;
+
+    let image = Image::from_fn( width, height, |x, y| match fabric[ y * width + x ] {
+        0   => export::BLACK,
+        1   => export::GRAY,
+        n   => export::gradient( export::YELLOW, export::RED, (n - 2) as f64 / 4.0 )
+    } );
+
+    image.save( output )
+}
+
+/// Like `export`, but the colors go from gray through yellow to red over
+/// every depth up to the deepest one, instead of stopping at six claims.
+pub fn heatmap<I: Iterator<Item=String>>( input: I, output: &str ) -> Result<(), Box<dyn std::error::Error>> {
+    let (fabric, width, height) = fabric( input )?;
+    let max = fabric.iter().copied().max().unwrap_or( 0 );
+
+    let image = Image::from_fn( width, height, |x, y| {
+        let t = match fabric[ y * width + x ] {
+            0   => return export::BLACK,
+            n   => (n - 1) as f64 / (max - 1).max( 1 ) as f64
+        };
+
+        if t < 0.5 {
+            export::gradient( export::GRAY, export::YELLOW, 2.0 * t )
+        } else {
+            export::gradient( export::YELLOW, export::RED, 2.0 * t - 1.0 )
+        }
+    } );
+
+    image.save( output )
+}
+
+/// Whether two claims share at least one square inch.
+pub fn overlaps( a: &Claim, b: &Claim ) -> bool {
+    has_area( a ) && has_area( b ) &&
+    a.position.0 < b.position.0 + b.size.0 && b.position.0 < a.position.0 + a.size.0 &&
+    a.position.1 < b.position.1 + b.size.1 && b.position.1 < a.position.1 + a.size.1
+}
+
+/// Side of the fabric `generate` spreads `size` claims over, growing with
+/// the number of claims so huge inputs are as crowded as the puzzle's.
+pub fn fabric_side( size: usize ) -> usize {
+    1000.max( (size as f64).sqrt() as usize * 27 )
+}
+
+/// Claim of random size somewhere on a fabric of the given side, overlapping
+/// `near` if given.
+pub fn random_claim( rng: &mut Rng, side: usize, near: Option<&Claim> ) -> Claim {
+    let (w, h) = (rng.between( 10, 29 ) as usize, rng.between( 10, 29 ) as usize);
+
+    // NOTE: Overlapping `near` means starting less than a width before it
+    // and before its end, all while staying on the fabric
+    let (x, y) = match near {
+        Some( c ) => (
+            rng.between( c.position.0.saturating_sub( w - 1 ) as i64, (c.position.0 + c.size.0 - 1).min( side - w ) as i64 ),
+            rng.between( c.position.1.saturating_sub( h - 1 ) as i64, (c.position.1 + c.size.1 - 1).min( side - h ) as i64 )
+        ),
+        None      => (rng.between( 0, (side - w) as i64 ), rng.between( 0, (side - h) as i64 ))
+    };
+
+    Claim { id: 0, position: (x as usize, y as usize), size: (w, h) }
+}
+
+/// Claims come in overlapping pairs (the odd one out overlaps a random
+/// earlier claim), except for a single claim that overlaps nothing.
+pub fn generate( rng: &mut Rng, size: usize ) -> Vec<String> {
+    let side = fabric_side( size );
+    let others = size.max( 3 ) - 1;
+    let intact = random_claim( rng, side, None );
+    let mut claims = vec![];
+
+    while claims.len() < others {
+        let n = claims.len();
+        let near = if n % 2 == 1 {
+            Some( n - 1 )
+        } else if n + 1 == others {
+            Some( rng.below( n ) )
+        } else {
+            None
+        };
+
+        let claim = random_claim( rng, side, near.map( |i| &claims[ i ] ) );
+        if ! overlaps( &claim, &intact ) {
+            claims.push( claim );
+        }
+    }
+
+    claims.push( intact );
+    rng.shuffle( &mut claims );
+
+    claims
+        .iter()
+        .enumerate()
+        .map( |(i, c)| Claim { id: i + 1, ..c.clone() }.to_string() )
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc::gen::Rng;
+    use proptest::prelude::*;
+
+    fn claim() -> impl Strategy<Value=(usize, usize, usize, usize, usize)> {
+        (0 .. 10_000usize, 0 .. 1000usize, 0 .. 1000usize, 1 .. 100usize, 1 .. 100usize)
+    }
+
+    fn small_claim() -> impl Strategy<Value=(usize, usize, usize, usize, usize)> {
+        (1 .. 100usize, 0 .. 100usize, 0 .. 100usize, 1 .. 50usize, 1 .. 50usize)
+    }
+
+    fn format_claim( (id, x, y, w, h): (usize, usize, usize, usize, usize) ) -> String {
+        format!( "#{} @ {},{}: {}x{}", id, x, y, w, h )
+    }
+
+    #[test]
+    fn errors_point_at_the_field() {
+        let error = |line: &str| line.parse::<Claim>().unwrap_err();
+
+        assert_eq!( error( "#1 @ 3,2: 5x4x" ).to_string(), "invalid claim: expected end of line at column 14, found 'x'" );
+        assert_eq!( error( "#1 @ 3,2: 5y4" ).to_string(), "invalid claim: expected 'x' at column 12, found 'y'" );
+        assert_eq!( error( "#1 @ 3,2:" ).to_string(), "invalid claim: expected ': ' at column 9, found ':'" );
+        assert_eq!( error( "#1 @ 3,2: 5x" ).to_string(), "invalid claim: expected height at column 13, found end of line" );
+        assert_eq!( error( "#1 @3,2: 5x4" ).to_string(), "invalid claim: expected ' @ ' at column 3, found ' @3'" );
+        assert_eq!( error( "1 @ 3,2: 5x4" ).to_string(), "invalid claim: expected '#' at column 1, found '1'" );
+        assert_eq!( error( "#1 @ ü,2: 5x4" ).to_string(), "invalid claim: expected x at column 6, found 'ü'" );
+        assert_eq!( error( "#1 @ 3,ü: 5x4" ).column, 8 );
+
+        let too_big = error( "#99999999999999999999999 @ 3,2: 5x4" );
+        assert_eq!( (too_big.field, too_big.column, too_big.problem), ("id", 2, Problem::TooBig) );
+
+        let too_far = error( &format!( "#1 @ 3,{}: 5x4", usize::MAX ) );
+        assert_eq!( (too_far.field, too_far.found.as_str(), too_far.problem), ("height", "4", Problem::TooFar) );
+    }
+
+    #[test]
+    fn references_agree() {
+        assert!( diff::check( 30, 300, 0, generate, &CHECKS ).unwrap().is_none() );
+    }
+
+    #[test]
+    fn parse_rejects_repeated_ids() {
+        let input = [ "#1 @ 1,3: 4x4", "#2 @ 3,1: 4x4", "#1 @ 5,5: 2x2" ];
+        let claims = parse( input.iter().map( |l| l.to_string() ) );
+
+        assert_eq!( claims.unwrap_err(), "line 3: claim #1 is already on line 1" );
+        assert_eq!( parse( [ "#1 @ 1,3: 4x" ].iter().map( |l| l.to_string() ) ).unwrap_err(), "line 1: invalid claim: expected height at column 13, found end of line" );
+    }
+
+    #[test]
+    fn overlaps_answer_queries() {
+        let input = [ "#1 @ 1,3: 4x4", "#2 @ 3,1: 4x4", "#3 @ 5,5: 2x2", "#4 @ 6,6: 3x3", "#5 @ 20,20: 1x1", "#6 @ 1,1: 1x1" ];
+        let overlaps = Overlaps::new( parse( input.iter().map( |l| l.to_string() ) ).unwrap() );
+        let eval = |line| repl::eval( &overlaps, &COMMANDS, line );
+
+        assert_eq!( eval( "intact" ), Ok( "#5 #6".into() ) );
+        assert_eq!( eval( "conflicts 1" ), Ok( "#2 shares 4".into() ) );
+        assert_eq!( eval( "conflicts #4" ), Ok( "#3 shares 1".into() ) );
+        assert_eq!( eval( "conflicts 5" ), Ok( "none".into() ) );
+        assert_eq!( eval( "component 2" ), Ok( "#1 #2".into() ) );
+        assert_eq!( eval( "component 6" ), Ok( "#6".into() ) );
+        assert_eq!( eval( "components" ), Ok( "#1 #2\n#3 #4".into() ) );
+        assert!( eval( "conflicts 7" ).is_err() );
+        assert_eq!( solve_part2( &overlaps.claims ), Some( 5 ) );
+        assert_eq!( part2( input[ .. 2 ].iter().map( |l| l.to_string() ) ), Err( "no claim is intact".into() ) );
+    }
+
+    #[test]
+    fn claims_without_area_overlap_nothing() {
+        let input = [ "#1 @ 1,1: 4x4", "#2 @ 2,2: 0x2", "#3 @ 0,3: 6x0", "#4 @ 3,3: 2x2" ];
+        let claims = parse( input.iter().map( |l| l.to_string() ) ).unwrap();
+
+        assert!( ! overlaps( &claims[ 0 ], &claims[ 1 ] ) );
+        assert!( ! overlaps( &claims[ 2 ], &claims[ 0 ] ) );
+        assert!( overlaps( &claims[ 0 ], &claims[ 3 ] ) );
+        assert_eq!( overlapping_pairs( &claims ), vec![ (0, 3) ] );
+        assert_eq!( Overlaps::new( claims.clone() ).intact(), vec![ 1, 2 ] );
+        assert_eq!( solve_part2( &claims ), Some( 2 ) );
+    }
+
+    proptest! {
+        #[test]
+        fn claim_parses( c in claim() ) {
+            let claim = format_claim( c ).parse::<Claim>().unwrap();
+
+            prop_assert_eq!( claim.id, c.0 );
+            prop_assert_eq!( claim.position, (c.1, c.2) );
+            prop_assert_eq!( claim.size, (c.3, c.4) );
+            prop_assert_eq!( claim.to_string(), format_claim( c ) );
+        }
+
+        #[test]
+        fn claim_round_trips( id: usize, x: usize, y: usize, w: usize, h: usize ) {
+            let claim = Claim { id, position: (x, y), size: (w.min( usize::MAX - x ), h.min( usize::MAX - y )) };
+            prop_assert_eq!( claim.to_string().parse::<Claim>(), Ok( claim ) );
+        }
+
+        #[test]
+        fn claim_rejects_malformed( c in claim(), junk in "[a-z]{1,4}" ) {
+            let (id, x, y, w, h) = c;
+            let malformed = [
+                String::new(),
+                format!( "{} @ {},{}: {}x{}", id, x, y, w, h ),
+                format!( "#{} {},{}: {}x{}", id, x, y, w, h ),
+                format!( "#{} @ {},{} {}x{}", id, x, y, w, h ),
+                format!( "#{} @ {}{}: {}x{}", id, x, y, w, h ),
+                format!( "#{} @ {},{}: {}{}", id, x, y, w, h ),
+                format!( "#{} @ {},{}: {}", id, x, y, w ),
+                format!( "#{} @ {},{}", id, x, y ),
+                format!( "#{} @ {},{}: {}x{}", junk, x, y, w, h ),
+                format!( "#{} @ {},{}: {}x{}", id, junk, y, w, h ),
+                format!( "#{} @ {},{}: {}x{}", id, x, junk, w, h ),
+                format!( "#{} @ {},{}: {}x{}", id, x, y, junk, h ),
+                format!( "#{} @ {},{}: {}x{}", id, x, y, w, junk ),
+                format!( "#-{} @ {},{}: {}x{}", id, x, y, w, h ),
+                format!( "#{} @ -{},{}: {}x{}", id, x, y, w, h ),
+                format!( "#{} @ {},{}: {}x{}", id, usize::MAX - w + 1, y, w, h ),
+                format!( "#{} @ {},{}: {}x{}{}", id, x, y, w, h, junk ),
+                format!( "#{} @ {},{}: {}x{} ", id, x, y, w, h ),
+                format!( " #{} @ {},{}: {}x{}", id, x, y, w, h ),
+                format!( "#{} @ {}, {}: {}x{}", id, x, y, w, h ),
+                format!( "#+{} @ {},{}: {}x{}", id, x, y, w, h )
+            ];
+
+            for line in &malformed {
+                prop_assert!( line.parse::<Claim>().is_err(), "accepted '{}'", line );
+            }
+        }
+
+        #[test]
+        fn part1_counts_overlaps( a in small_claim(), b in small_claim() ) {
+            let overlap = |(p, s): (usize, usize), (q, t): (usize, usize)| (p .. p + s).filter( |i| (q .. q + t).contains( i ) ).count();
+            let area = overlap( (a.1, a.3), (b.1, b.3) ) * overlap( (a.2, a.4), (b.2, b.4) );
+
+            let input = vec![ format_claim( (1, a.1, a.2, a.3, a.4) ), format_claim( (2, b.1, b.2, b.3, b.4) ) ];
+            prop_assert_eq!( part1( input.into_iter() ), Ok( area as u128 ) );
+        }
+
+        #[test]
+        fn part1_agrees_with_counting_squares( claims in prop::collection::vec( small_claim(), 0 .. 12 ), far in any::<bool>() ) {
+            // NOTE: Far away claims have the same overlaps, just moved
+            let shift = if far { usize::MAX / 2 } else { 0 };
+            let mut fabric = vec![ 0; 150 * 150 ];
+
+            for &(_, x, y, w, h) in &claims {
+                for i in y .. y + h {
+                    for j in x .. x + w {
+                        fabric[ i * 150 + j ] += 1;
+                    }
+                }
+            }
+
+            let input = claims.iter().enumerate().map( |(id, &(_, x, y, w, h))| format_claim( (id, x + shift, y + shift, w, h) ) );
+            prop_assert_eq!( part1( input ), Ok( fabric.iter().filter( |&&n| n > 1 ).count() as u128 ) );
+        }
+
+        #[test]
+        fn depths_agree_with_counting_squares( claims in prop::collection::vec( small_claim(), 1 .. 12 ) ) {
+            let mut fabric = vec![ 0; 150 * 150 ];
+
+            for &(_, x, y, w, h) in &claims {
+                for i in y .. y + h {
+                    for j in x .. x + w {
+                        fabric[ i * 150 + j ] += 1;
+                    }
+                }
+            }
+
+            let claims = claims.into_iter().map( |c| format_claim( c ).parse::<Claim>().unwrap() ).collect::<Vec<_>>();
+            let width = claims.iter().map( |c| corners( c ).1.0 ).max().unwrap();
+            let height = claims.iter().map( |c| corners( c ).1.1 ).max().unwrap();
+            let depths = depths( &claims );
+
+            let max = *fabric.iter().max().unwrap();
+            let deepest = (0 .. 150 * 150).filter( |&i| fabric[ i ] == max ).map( |i| (i % 150, i / 150) ).min();
+
+            prop_assert_eq!( depths.max_depth(), max );
+            prop_assert_eq!( depths.deepest, deepest );
+            prop_assert_eq!( depths.area.iter().skip( 2 ).sum::<u128>(), solve_part1( &claims ) );
+
+            for (k, &area) in depths.area.iter().enumerate() {
+                let squares = (0 .. height).flat_map( |y| (0 .. width).map( move |x| (x, y) ) ).filter( |&(x, y)| fabric[ y * 150 + x ] == k );
+                prop_assert_eq!( area, squares.count() as u128 );
+            }
+        }
+
+        #[test]
+        fn overlapping_pairs_agree_with_comparing_all( claims in prop::collection::vec( small_claim(), 0 .. 20 ) ) {
+            let claims = claims.into_iter().map( |c| format_claim( c ).parse::<Claim>().unwrap() ).collect::<Vec<_>>();
+            let expected = (0 .. claims.len())
+                            .flat_map( |j| (0 .. j).map( move |i| (i, j) ) )
+                            .filter( |&(i, j)| overlaps( &claims[ i ], &claims[ j ] ) )
+                            .collect::<Vec<_>>();
+
+            let mut found = overlapping_pairs( &claims );
+            found.sort_by_key( |&(i, j)| (j, i) );
+
+            prop_assert_eq!( found, expected );
+        }
+
+        #[test]
+        fn part2_finds_the_only_intact_claim( a in small_claim(), b in small_claim() ) {
+            let (a, c, b) = ((1, a.1, a.2, a.3, a.4), (3, a.1, a.2, a.3, a.4), (2, b.1 + 200, b.2, b.3, b.4));
+            let input = vec![ format_claim( a ), format_claim( c ), format_claim( b ) ];
+
+            prop_assert_eq!( part2( input.into_iter() ), Ok( 2 ) );
+        }
+
+        #[test]
+        fn generated_input_has_one_intact_claim( seed: u64, size in 3 .. 100usize ) {
+            let input = generate( &mut Rng::new( seed ), size );
+            let claims = input.iter().map( |l| l.parse::<Claim>().unwrap() ).collect::<Vec<_>>();
+
+            let intact = claims
+                            .iter()
+                            .filter( |&a| claims.iter().all( |b| a.id == b.id || ! overlaps( a, b ) ) )
+                            .map( |c| c.id )
+                            .collect::<Vec<_>>();
+
+            prop_assert_eq!( claims.len(), size );
+            prop_assert_eq!( intact.len(), 1 );
+            prop_assert_eq!( part2( input.into_iter() ), Ok( intact[ 0 ] ) );
+        }
+    }
+}
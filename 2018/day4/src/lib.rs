@@ -0,0 +1,310 @@
+//! Guard logs, e.g. `[1518-11-01 00:05] falls asleep`, and who sleeps the most.
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use aoc::gen::Rng;
+use aoc::{debug, info};
+use nom::*;
+
+/// What happened at the time of a log entry.
+#[derive(Debug)]
+pub enum Message {
+    BeginsShift(usize),
+    FallsAsleep,
+    WakesUp
+}
+
+/// A single line of the log.
+#[derive(Debug)]
+pub struct LogEntry {
+    pub date: (usize, usize, usize),
+    pub time: (usize, usize),
+    pub message: Message
+}
+
+named!(pub parse_message(&str) -> Message,
+    do_parse!(
+        result: alt!(
+            do_parse!(
+                tag!( "Guard #" ) >>
+                guard: map_res!( digit, usize::from_str ) >>
+                (Message::BeginsShift( guard ))
+            ) |
+            do_parse!(
+                tag!( "falls asleep" ) >>
+                (Message::FallsAsleep)
+            ) |
+            do_parse!(
+                tag!( "wakes up" ) >>
+                (Message::WakesUp)
+            )
+        ) >> (result)
+    )
+);
+
+named!(pub parse_log(&str) -> LogEntry,
+    do_parse!(
+        char!( '[' )                                    >>
+        year: map_res!( digit, usize::from_str )        >>
+        char!( '-' )                                    >>
+        month: map_res!( digit, usize::from_str )       >>
+        char!( '-' )                                    >>
+        day: map_res!( digit, usize::from_str )         >>
+        many1!( space )                                 >>
+        hour: map_res!( digit, usize::from_str )        >>
+        char!( ':' )                                    >>
+        minutes: map_res!( digit, usize::from_str )     >>
+        char!( ']' )                                    >>
+        many1!( space )                                 >>
+        message: parse_message                          >>
+        (LogEntry {
+            date: (year, month, day),
+            time: (hour, minutes),
+            message
+        })
+    )
+);
+
+/// Log entries in chronological order, whatever order the lines came in.
+pub fn parse<I: Iterator<Item=String>>( input: I ) -> Result<Vec<LogEntry>, String> {
+    let mut logs = input.collect::<Vec<_>>();
+    logs.sort();
+
+    logs.iter()
+        .map( |l| parse_log( l ).map( |(_, entry)| entry ).map_err( |_| format!( "invalid log entry: '{}'", l ) ) )
+        .collect()
+}
+
+// Traces a guard taking over from the previous one.
+fn shift( entry: &LogEntry, previous: Option<usize> ) {
+    if let Message::BeginsShift( gid ) = entry.message {
+        let (y, m, d) = entry.date;
+        let (hour, minute) = entry.time;
+
+        match previous {
+            Some( previous )    => info!( "{}-{:02}-{:02} {:02}:{:02} guard #{} takes over from #{}", y, m, d, hour, minute, gid, previous ),
+            None                => info!( "{}-{:02}-{:02} {:02}:{:02} guard #{} begins the first shift", y, m, d, hour, minute, gid )
+        }
+    }
+}
+
+/// ID of the guard who sleeps the most, times the minute they sleep most often.
+pub fn solve_part1( logs: &[LogEntry] ) -> usize {
+    let mut guard = None;
+    let mut sleeping = 0;
+
+    let mut guards = HashMap::<usize, (usize, Vec<usize>)>::default();
+
+    for entry in logs {
+        if let Some( gid ) = guard {
+            match entry.message {
+                Message::FallsAsleep        => sleeping = entry.time.1,
+                Message::WakesUp            => {
+                    debug!( "guard #{} sleeps from 00:{:02} to 00:{:02}", gid, sleeping, entry.time.1 );
+
+                    let e = guards.entry( gid ).or_insert( (0, vec![0; 60]) );
+                    e.0 += entry.time.1 - sleeping;
+                    for i in sleeping .. entry.time.1 {
+                        e.1[ i ] += 1;
+                    }
+                },
+                Message::BeginsShift( new_gid ) => {
+                    shift( entry, Some( gid ) );
+                    guard = Some( new_gid );
+                }
+            }
+        } else {
+            if let Message::BeginsShift( gid ) = entry.message {
+                shift( entry, None );
+                guard = Some( gid );
+            }
+        }
+    }
+
+    let (gid, (_, schedule)) = guards.into_iter().max_by_key( |v| (v.1).0 ).unwrap();
+
+    gid * schedule.into_iter().enumerate().max_by_key( |&(_, v)| v ).unwrap().0
+}
+
+/// ID of the guard who is most often asleep on the same minute, times that
+/// minute.
+pub fn solve_part2( logs: &[LogEntry] ) -> usize {
+    let mut guard = None;
+    let mut sleeping = 0;
+
+    let mut guards = HashMap::<usize, Vec<usize>>::default();
+
+    for entry in logs {
+        if let Some( gid ) = guard {
+            match entry.message {
+                Message::FallsAsleep        => sleeping = entry.time.1,
+                Message::WakesUp            => {
+                    debug!( "guard #{} sleeps from 00:{:02} to 00:{:02}", gid, sleeping, entry.time.1 );
+
+                    let e = guards.entry( gid ).or_insert( vec![0; 60] );
+                    for i in sleeping .. entry.time.1 {
+                        (*e)[ i ] += 1;
+                    }
+                },
+                Message::BeginsShift( new_gid ) => {
+                    shift( entry, Some( gid ) );
+                    guard = Some( new_gid );
+                }
+            }
+        } else {
+            if let Message::BeginsShift( gid ) = entry.message {
+                shift( entry, None );
+                guard = Some( gid );
+            }
+        }
+    }
+
+    guards
+        .into_iter()
+        .map( |(guard, schedule)| (guard, schedule.into_iter().enumerate().max_by_key( |&(_, v)| v ).unwrap()) )
+        .max_by_key( |&(_, (_, v))| v )
+        .map( |(g, (m, _))| g * m )
+        .unwrap()
+}
+
+/// `solve_part1` straight from the input lines.
+pub fn part1<I: Iterator<Item=String>>( input: I ) -> Result<usize, String> {
+    Ok( solve_part1( &parse( input )? ) )
+}
+
+/// `solve_part2` straight from the input lines.
+pub fn part2<I: Iterator<Item=String>>( input: I ) -> Result<usize, String> {
+    Ok( solve_part2( &parse( input )? ) )
+}
+
+/// Date `day` days after the start of 1518, ignoring leap years.
+pub fn date( mut day: usize ) -> (usize, usize, usize) {
+    const MONTHS: [usize; 12] = [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
+
+    let year = 1518 + day / 365;
+    day %= 365;
+
+    let mut month = 0;
+    while day >= MONTHS[ month ] {
+        day -= MONTHS[ month ];
+        month += 1;
+    }
+
+    (year, month + 1, day + 1)
+}
+
+/// One shift per night for `size` nights, shuffled just like the real log.
+pub fn generate( rng: &mut Rng, size: usize ) -> Vec<String> {
+    let stamp = |day: usize, hour: usize, minute: usize| {
+        let (y, m, d) = date( day );
+        format!( "[{}-{:02}-{:02} {:02}:{:02}]", y, m, d, hour, minute )
+    };
+
+    let guards = (0 .. size / 20 + 1).map( |_| rng.between( 10, 3500 ) ).collect::<Vec<_>>();
+    let mut lines = vec![];
+
+    for night in 1 ..= size.max( 1 ) {
+        let guard = rng.pick( &guards );
+
+        if rng.chance( 0.5 ) {
+            lines.push( format!( "{} Guard #{} begins shift", stamp( night - 1, 23, rng.between( 45, 59 ) as usize ), guard ) );
+        } else {
+            lines.push( format!( "{} Guard #{} begins shift", stamp( night, 0, rng.below( 5 ) ), guard ) );
+        }
+
+        // NOTE: At least one nap on the first night, so somebody sleeps
+        let naps = if night == 1 { 1 + rng.below( 3 ) } else { rng.below( 4 ) };
+        let mut minutes = (0 .. naps * 2).map( |_| 5 + rng.below( 55 ) ).collect::<Vec<_>>();
+        minutes.sort();
+        minutes.dedup();
+
+        for nap in minutes.chunks_exact( 2 ) {
+            lines.push( format!( "{} falls asleep", stamp( night, 0, nap[ 0 ] ) ) );
+            lines.push( format!( "{} wakes up", stamp( night, 0, nap[ 1 ] ) ) );
+        }
+    }
+
+    rng.shuffle( &mut lines );
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc::gen::Rng;
+    use proptest::prelude::*;
+
+    fn stamp() -> impl Strategy<Value=(usize, usize, usize, usize, usize)> {
+        (1500 .. 1600usize, 1 .. 13usize, 1 .. 29usize, 0 .. 24usize, 0 .. 60usize)
+    }
+
+    fn format_stamp( (y, m, d, h, min): (usize, usize, usize, usize, usize) ) -> String {
+        format!( "[{}-{:02}-{:02} {:02}:{:02}]", y, m, d, h, min )
+    }
+
+    proptest! {
+        #[test]
+        fn parse_log_reads_every_message( t in stamp(), guard in 0 .. 10_000usize, kind in 0 .. 3 ) {
+            let message = match kind {
+                0 => format!( "Guard #{} begins shift", guard ),
+                1 => "falls asleep".into(),
+                _ => "wakes up".into()
+            };
+
+            let entry = parse_log( &format!( "{} {}", format_stamp( t ), message ) ).unwrap().1;
+
+            prop_assert_eq!( entry.date, (t.0, t.1, t.2) );
+            prop_assert_eq!( entry.time, (t.3, t.4) );
+
+            match entry.message {
+                Message::BeginsShift( g )   => prop_assert!( kind == 0 && g == guard ),
+                Message::FallsAsleep        => prop_assert_eq!( kind, 1 ),
+                Message::WakesUp            => prop_assert_eq!( kind, 2 )
+            }
+        }
+
+        #[test]
+        fn parse_log_rejects_garbage( t in stamp(), junk in "[a-z ]{0,10}" ) {
+            prop_assume!( ! junk.starts_with( "falls asleep" ) && ! junk.starts_with( "wakes up" ) );
+
+            let line = format!( "{} {}", format_stamp( t ), junk );
+            prop_assert!( parse_log( &line ).is_err(), "accepted '{}'", line );
+        }
+
+        #[test]
+        fn parse_rejects_a_garbled_line( t in stamp(), junk in "[a-z ]{0,10}" ) {
+            prop_assume!( ! junk.starts_with( "falls asleep" ) && ! junk.starts_with( "wakes up" ) );
+
+            let logs = vec![ "[1518-11-01 00:00] Guard #10 begins shift".to_string(), format!( "{} {}", format_stamp( t ), junk ) ];
+            prop_assert!( parse( logs.into_iter() ).is_err() );
+        }
+
+        #[test]
+        fn sleepiest_minute_is_the_answer( guard in 1 .. 10_000usize, (from, to) in (0 .. 59usize).prop_flat_map( |f| (Just( f ), f + 1 .. 60) ) ) {
+            // NOTE: Logs come unsorted, parts are expected to sort them. The
+            // second, shorter nap makes `from` the single sleepiest minute.
+            let logs = vec![
+                format!( "[1518-11-01 00:{:02}] wakes up", to ),
+                format!( "[1518-11-02 00:{:02}] falls asleep", from ),
+                format!( "[1518-10-31 23:58] Guard #{} begins shift", guard ),
+                format!( "[1518-11-02 00:{:02}] wakes up", from + 1 ),
+                format!( "[1518-11-01 00:{:02}] falls asleep", from ),
+                format!( "[1518-11-02 00:00] Guard #{} begins shift", guard )
+            ];
+
+            prop_assert_eq!( part1( logs.clone().into_iter() ), Ok( guard * from ) );
+            prop_assert_eq!( part2( logs.into_iter() ), Ok( guard * from ) );
+        }
+
+        #[test]
+        fn generated_input_is_valid( seed: u64, size in 1 .. 500usize ) {
+            let input = generate( &mut Rng::new( seed ), size );
+            let shifts = input.iter().filter( |l| l.ends_with( "begins shift" ) ).count();
+
+            prop_assert_eq!( shifts, size );
+            prop_assert!( part1( input.iter().cloned() ).is_ok() );
+            prop_assert!( part2( input.into_iter() ).is_ok() );
+        }
+    }
+}
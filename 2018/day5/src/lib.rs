@@ -0,0 +1,195 @@
+//! Alchemical Reduction: polymers such as `dabAcCaCBAcCcaDA` and how they react.
+
+use aoc::diff;
+use aoc::gen::Rng;
+
+/// Unit of the same type and opposite polarity.
+pub trait Opposite {
+    fn opposite( self ) -> Self;
+}
+
+impl Opposite for char {
+    fn opposite( self ) -> Self {
+        if self.is_lowercase() { self.to_uppercase().next().unwrap() }
+        else if self.is_uppercase() { self.to_lowercase().next().unwrap() }
+        else { self }
+    }
+}
+
+/// Polymer left once every possible reaction took place.
+pub fn reduce<I: Iterator<Item=char>>( input: I ) -> Vec<char> {
+    let mut stack : Vec<char> = vec![];
+
+    for unit in input {
+        if let Some( last ) = stack.last() {
+            if last.opposite() == unit {
+                stack.pop();
+            } else {
+                stack.push( unit );
+            }
+        } else {
+            stack.push( unit );
+        }
+
+    }
+
+    stack
+}
+
+/// The polymer, on the first line.
+pub fn parse<I: Iterator<Item=String>>( mut input: I ) -> String {
+    input.next().unwrap()
+}
+
+/// Number of units left after the polymer fully reacts.
+pub fn solve_part1( polymer: &str ) -> usize {
+    let reduced = reduce( polymer.chars() );
+
+    reduced.len()
+}
+
+/// Length of the shortest polymer possible after removing a single unit type.
+pub fn solve_part2( polymer: &str ) -> usize {
+    let reduced = reduce( polymer.chars() );
+
+    let mut best = reduced.len();
+
+    for c in (b'a' ..= b'z').map( |c| c as char ) {
+        let new_reduced = reduce( reduced.iter().cloned().filter( |&m| m != c && m != c.opposite() ) );
+
+        if new_reduced.len() < best {
+            best = new_reduced.len()
+        }
+    }
+
+    best
+}
+
+/// `solve_part1` straight from the input lines.
+pub fn part1<I: Iterator<Item=String>>( input: I ) -> usize {
+    solve_part1( &parse( input ) )
+}
+
+/// `solve_part2` straight from the input lines.
+pub fn part2<I: Iterator<Item=String>>( input: I ) -> usize {
+    solve_part2( &parse( input ) )
+}
+
+/// Reference: reacts the first reacting pair over and over again, straight
+/// from the puzzle description.
+pub fn react_slowly( mut polymer: Vec<char> ) -> Vec<char> {
+    while let Some( i ) = (1 .. polymer.len()).find( |&i| polymer[ i - 1 ].opposite() == polymer[ i ] ) {
+        polymer.drain( i - 1 ..= i );
+    }
+
+    polymer
+}
+
+/// Reference for part one.
+pub fn reference_part1( input: &[String] ) -> String {
+    react_slowly( input[ 0 ].chars().collect() ).len().to_string()
+}
+
+/// Reference: every unit type is removed from the original polymer, not the
+/// already reduced one.
+pub fn reference_part2( input: &[String] ) -> String {
+    (b'a' ..= b'z')
+        .map( |c| c as char )
+        .map( |c| react_slowly( input[ 0 ].chars().filter( |&m| m != c && m != c.opposite() ).collect() ).len() )
+        .min()
+        .unwrap()
+        .to_string()
+}
+
+/// Optimised solutions paired with their references, for `diff-test`.
+pub const CHECKS: [diff::Check; 2] = [
+    ("part1", |input| part1( input.iter().cloned() ).to_string(), reference_part1),
+    ("part2", |input| part2( input.iter().cloned() ).to_string(), reference_part2)
+];
+
+/// Random units would hardly ever react, so every now and then the opposite
+/// of a still unmatched unit is emitted, which nests reactions like the real
+/// polymer does.
+pub fn generate( rng: &mut Rng, size: usize ) -> Vec<String> {
+    let mut open = Vec::<char>::new();
+    let mut polymer = String::with_capacity( size );
+
+    for _ in 0 .. size {
+        match open.pop() {
+            Some( unit ) if rng.chance( 0.45 ) => polymer.push( unit.opposite() ),
+            last                                => {
+                open.extend( last );
+
+                let unit = (b'a' + rng.below( 26 ) as u8) as char;
+                let unit = if rng.chance( 0.5 ) { unit } else { unit.opposite() };
+
+                open.push( unit );
+                polymer.push( unit );
+            }
+        }
+    }
+
+    vec![ polymer ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc::gen::Rng;
+    use proptest::prelude::*;
+
+    fn polymer() -> impl Strategy<Value=String> {
+        "[a-dA-D]{0,64}"
+    }
+
+    fn is_subsequence( small: &[char], big: &[char] ) -> bool {
+        let mut big = big.iter();
+        small.iter().all( |c| big.any( |b| b == c ) )
+    }
+
+    #[test]
+    fn references_agree() {
+        assert!( diff::check( 50, 300, 0, generate, &CHECKS ).unwrap().is_none() );
+    }
+
+    proptest! {
+        #[test]
+        fn reduce_is_idempotent( p in polymer() ) {
+            let once = reduce( p.chars() );
+            prop_assert_eq!( reduce( once.iter().cloned() ), once );
+        }
+
+        #[test]
+        fn reduce_preserves_order( p in polymer() ) {
+            let input = p.chars().collect::<Vec<_>>();
+            prop_assert!( is_subsequence( &reduce( p.chars() ), &input ) );
+        }
+
+        #[test]
+        fn reduce_leaves_no_reacting_units( p in polymer() ) {
+            let reduced = reduce( p.chars() );
+
+            prop_assert!( reduced.windows( 2 ).all( |w| w[ 0 ].opposite() != w[ 1 ] ) );
+            prop_assert_eq!( (p.len() - reduced.len()) % 2, 0 );
+        }
+
+        #[test]
+        fn part2_matches_reducing_from_scratch( p in polymer() ) {
+            let best = (b'a' ..= b'z')
+                            .map( |c| c as char )
+                            .map( |c| reduce( p.chars().filter( |&m| m != c && m != c.opposite() ) ).len() )
+                            .min()
+                            .unwrap();
+
+            prop_assert_eq!( part2( std::iter::once( p ) ).to_string(), best.to_string() );
+        }
+
+        #[test]
+        fn generated_polymer_reacts( seed: u64, size in 100 .. 1000usize ) {
+            let polymer = generate( &mut Rng::new( seed ), size ).remove( 0 );
+
+            prop_assert_eq!( polymer.len(), size );
+            prop_assert!( reduce( polymer.chars() ).len() < size );
+        }
+    }
+}
@@ -0,0 +1,253 @@
+//! Chronal Coordinates: points such as `1, 6` and the areas closest to them.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::num::ParseIntError;
+use std::str::FromStr;
+
+use aoc::export::{self, Image};
+use aoc::gen::Rng;
+
+/// Why a line isn't a valid pair of coordinates.
+#[derive(Default)]
+pub struct ParseCoordError(Option<ParseIntError>);
+
+impl fmt::Debug for ParseCoordError {
+    fn fmt( &self, f: &mut fmt::Formatter ) -> fmt::Result {
+        if let Some( e ) = &self.0 {
+            fmt::Debug::fmt( e,  f )
+        } else {
+            write!( f, "invalid coordinates" )
+        }
+    }
+}
+
+impl From<ParseIntError> for ParseCoordError {
+    fn from( error: ParseIntError ) -> Self {
+        ParseCoordError( Some( error ) )
+    }
+}
+
+/// Point on the grid as `(x, y)`.
+#[derive(Debug, PartialEq)]
+pub struct Coord(pub i64, pub i64);
+
+impl fmt::Display for Coord {
+    fn fmt( &self, f: &mut fmt::Formatter ) -> fmt::Result {
+        write!( f, "{}, {}", self.0, self.1 )
+    }
+}
+
+impl FromStr for Coord {
+    type Err = ParseCoordError;
+
+    fn from_str( s: &str ) -> Result<Self, Self::Err> {
+        let mut split = s.split( ", " );
+
+        Ok( Coord(
+            split.next().ok_or( ParseCoordError::default() ).and_then( |l| l.parse().map_err( Into::into ) )?,
+            split.next().ok_or( ParseCoordError::default() ).and_then( |l| l.parse().map_err( Into::into ) )?
+        ) )
+    }
+}
+
+/// Smallest box with all of the points, as `(Coord( xmin, xmax ), Coord( ymin, ymax ))`.
+pub fn points_bbox( points: &[Coord] ) -> (Coord, Coord) {
+    let (mut xmin, mut xmax) = (points[ 0 ].0, points[ 0 ].0);
+    let (mut ymin, mut ymax) = (points[ 0 ].1, points[ 0 ].1);
+
+    for point in points {
+        xmin = i64::min( xmin, point.0 );
+        xmax = i64::max( xmax, point.0 );
+        ymin = i64::min( ymin, point.1 );
+        ymax = i64::max( ymax, point.1 );
+    }
+
+    (Coord( xmin, xmax ), Coord( ymin, ymax ))
+}
+
+/// Index of the point closest to `(x, y)`, unless two or more are equally close.
+pub fn closest( points: &[Coord], x: i64, y: i64 ) -> Option<usize> {
+    let mut dists = points
+                        .iter()
+                        .enumerate()
+                        .map( |(i,p)| ((x - p.0).abs() + (y - p.1).abs(), i) )
+                        .collect::<Vec<_>>();
+
+    dists.sort();
+
+    if dists[ 0 ].0 != dists[ 1 ].0 {
+        Some( dists[ 0 ].1 )
+    } else {
+        None
+    }
+}
+
+/// Points, one per line.
+pub fn parse<I: Iterator<Item=String>>( input: I ) -> Vec<Coord> {
+    input.map( |l| l.parse::<Coord>().unwrap() ).collect()
+}
+
+/// Size of the largest area that isn't infinite.
+pub fn solve_part1( points: &[Coord] ) -> i32 {
+    let (Coord( xmin, xmax ), Coord( ymin, ymax )) = points_bbox( points );
+    let mut size = vec![0; points.len()];
+
+    for y in ymin ..= ymax {
+        for x in xmin ..= xmax {
+            if let Some( i ) = closest( points, x, y ) {
+                if x == xmin || x == xmax || y == ymin || y == ymax {
+                    size[ i ] = -1;
+                } else if size[ i ] != -1 {
+                    size[ i ] += 1;
+                }
+            }
+        }
+    }
+
+    size.into_iter()
+        .max()
+        .unwrap()
+}
+
+/// Total distance to all points a location in the safe region stays below.
+pub const THRESHOLD: i64 = 10_000;
+
+/// Size of the region with a total distance to all points below `threshold`.
+// NOTE: Only locations within the bounding box count, which is plenty for
+// thresholds as small as the puzzle's
+pub fn safe_region( points: &[Coord], threshold: i64 ) -> usize {
+    let (Coord( xmin, xmax ), Coord( ymin, ymax )) = points_bbox( points );
+    let mut size = 0;
+
+    for y in ymin ..= ymax {
+        for x in xmin ..= xmax {
+            let dists = points
+                            .iter()
+                            .map( |p| (x - p.0).abs() + (y - p.1).abs() )
+                            .sum::<i64>();
+
+            if dists < threshold {
+                size += 1;
+            }
+        }
+    }
+
+    size
+}
+
+/// Size of the region with a total distance to all points below 10000.
+pub fn solve_part2( points: &[Coord] ) -> usize {
+    safe_region( points, THRESHOLD )
+}
+
+/// `solve_part1` straight from the input lines.
+pub fn part1<I: Iterator<Item=String>>( input: I ) -> i32 {
+    solve_part1( &parse( input ) )
+}
+
+/// `solve_part2` straight from the input lines.
+pub fn part2<I: Iterator<Item=String>>( input: I ) -> usize {
+    solve_part2( &parse( input ) )
+}
+
+/// Saves the areas as a PNG image, every point with its own color.
+pub fn export<I: Iterator<Item=String>>( input: I, output: &str ) -> Result<(), Box<dyn std::error::Error>> {
+    let points = input
+                    .map( |l| l.parse::<Coord>().unwrap() )
+                    .collect::<Vec<_>>();
+
+    let (Coord( xmin, xmax ), Coord( ymin, ymax )) = points_bbox( & points );
+    let (width, height) = ((xmax - xmin + 1) as usize, (ymax - ymin + 1) as usize);
+
+    let mut image = Image::from_fn( width, height, |x, y| {
+        match closest( &points, x as i64 + xmin, y as i64 + ymin ) {
+            Some( i )   => export::palette( i ),
+            None        => export::BLACK
+        }
+    } );
+
+    for point in &points {
+        image.set_signed( point.0 - xmin, point.1 - ymin, export::WHITE );
+    }
+
+    image.scaled( 2 ).save( output )
+}
+
+/// Distinct points, spread over an area which grows with their number.
+pub fn generate( rng: &mut Rng, size: usize ) -> Vec<String> {
+    let size = size.max( 2 );
+    let spread = ((size as f64).sqrt() * 45.0) as i64 + 50;
+    let mut seen = HashSet::new();
+
+    while seen.len() < size {
+        seen.insert( (rng.between( 40, 40 + spread ), rng.between( 40, 40 + spread )) );
+    }
+
+    let mut points = seen.into_iter().collect::<Vec<_>>();
+    points.sort();
+    rng.shuffle( &mut points );
+
+    points.into_iter().map( |(x, y)| Coord( x, y ).to_string() ).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc::gen::Rng;
+    use proptest::prelude::*;
+
+    fn distance( p: &Coord, x: i64, y: i64 ) -> i64 {
+        (x - p.0).abs() + (y - p.1).abs()
+    }
+
+    proptest! {
+        #[test]
+        fn coord_round_trips( x in any::<i64>(), y in any::<i64>() ) {
+            let coord = Coord( x, y );
+            prop_assert_eq!( coord.to_string().parse::<Coord>().unwrap(), coord );
+        }
+
+        #[test]
+        fn coord_rejects_garbage( x in any::<i64>(), s in "[^0-9-]{1,8}" ) {
+            let malformed = [
+                format!( "{}, {}", x, s ),
+                format!( "{}", x ),
+                format!( "{}; {}", x, x )
+            ];
+
+            for line in &malformed {
+                prop_assert!( line.parse::<Coord>().is_err(), "accepted '{}'", line );
+            }
+        }
+
+        #[test]
+        fn closest_is_unique_minimum(
+            points in prop::collection::vec( (-50i64 .. 50, -50i64 .. 50), 2 .. 10 ),
+            x in -60i64 .. 60,
+            y in -60i64 .. 60
+        ) {
+            let points = points.into_iter().map( |(x, y)| Coord( x, y ) ).collect::<Vec<_>>();
+            let best = points.iter().map( |p| distance( p, x, y ) ).min().unwrap();
+            let at_best = points.iter().filter( |p| distance( p, x, y ) == best ).count();
+
+            match closest( &points, x, y ) {
+                Some( i )   => {
+                    prop_assert_eq!( at_best, 1 );
+                    prop_assert_eq!( distance( &points[ i ], x, y ), best );
+                },
+                None        => prop_assert!( at_best > 1 )
+            }
+        }
+
+        #[test]
+        fn generated_points_are_distinct( seed: u64, size in 2 .. 100usize ) {
+            let input = generate( &mut Rng::new( seed ), size );
+            let mut points = input.iter().map( |l| l.parse::<Coord>().unwrap() ).map( |c| (c.0, c.1) ).collect::<Vec<_>>();
+
+            points.sort();
+            points.dedup();
+            prop_assert_eq!( points.len(), size );
+        }
+    }
+}
@@ -0,0 +1,395 @@
+//! The Sum of Its Parts: steps such as `Step C must be finished before step A can begin.`
+
+use std::collections::BinaryHeap;
+use std::iter::FromIterator;
+
+use aoc::gen::Rng;
+use aoc::repl::{self, arg, arg_or};
+use aoc::{debug, info};
+use lazy_static::lazy_static;
+use regex::Regex;
+
+/// `graph[ a ][ b ]` is set when step `b` has to wait for step `a`, steps
+/// being numbered from 0 for `A`.
+pub type Graph = [[bool; 26]; 26];
+
+// NOTE: Reversed order, so the heap hands out the alphabetically first step
+#[derive(PartialEq, Eq)]
+struct Node(usize);
+
+impl PartialOrd for Node {
+    fn partial_cmp( &self, other: &Self ) -> Option<std::cmp::Ordering> {
+        Some( self.cmp( other ) )
+    }
+}
+
+impl Ord for Node {
+    fn cmp( &self, other: &Self ) -> std::cmp::Ordering {
+        other.0.cmp( &self.0 )
+    }
+}
+
+/// Both steps of a line, the first one has to be finished before the second.
+pub fn parse_step( s: &str ) -> (char, char) {
+    lazy_static! {
+        static ref RE: Regex = Regex::new( r"Step ([A-Z]) must be finished before step ([A-Z]) can begin." ).unwrap();
+    }
+
+    let cap = RE.captures( s ).unwrap();
+    (cap[ 1 ].chars().next().unwrap(), cap[ 2 ].chars().next().unwrap())
+}
+
+/// Number of steps every step has to wait for.
+pub fn in_degrees( graph: &Graph ) -> Vec<usize> {
+    let mut in_degree = vec![0; graph.len()];
+
+    for l in graph {
+        let iter = l.iter()
+                    .enumerate()
+                    .filter( |(_, &v)| v );
+
+        for (i, _) in iter {
+            in_degree[ i ] += 1;
+        }
+    }
+
+    in_degree
+}
+
+/// Whether the step shows up in the graph at all.
+// NOTE: Steps which never show up in the input are not part of the graph
+pub fn is_step( graph: &Graph, step: usize ) -> bool {
+    graph[ step ].iter().any( |&v| v ) || graph.iter().any( |l| l[ step ] )
+}
+
+/// Order in which the steps get done, alphabetically first one whenever
+/// there is a choice.
+pub fn toposort( graph: &Graph ) -> Vec<usize> {
+    let mut in_degree = in_degrees( graph );
+    let mut zero_degree = BinaryHeap::from_iter(
+        in_degree
+            .iter()
+            .enumerate()
+            .filter( |&(i, &n)| n == 0 && is_step( graph, i ) )
+            .map( |(i, _)| Node( i ) )
+    );
+
+    let mut sorted = vec![];
+
+    while let Some( Node( node ) ) = zero_degree.pop() {
+        sorted.push( node );
+
+        for (i, v) in graph[ node ].iter().enumerate() {
+            if !v { continue; }
+
+            in_degree[ i ] -= 1;
+
+            if in_degree[ i ] == 0 {
+                zero_degree.push( Node( i ) );
+            }
+        }
+
+    }
+
+    sorted
+}
+
+/// Graph of all the steps, one dependency per line.
+pub fn parse<I: Iterator<Item=String>>( input: I ) -> Graph {
+    let mut graph = [[false; 26]; 26];
+
+    for line in input {
+        let (from, to) = parse_step( &line );
+
+        graph[ from as usize - 65 ][ to as usize - 65 ] = true;
+    }
+
+    graph
+}
+
+fn letter( step: usize ) -> char {
+    (step as u8 + 65) as char
+}
+
+/// Order in which the steps get done.
+pub fn solve_part1( graph: &Graph ) -> String {
+    toposort( graph )
+        .into_iter()
+        .map( letter )
+        .collect::<String>()
+}
+
+/// Elves working on steps at the same time in part two.
+pub const WORKERS: usize = 5;
+
+/// Seconds every step takes on top of its number in part two.
+pub const BASE: usize = 60;
+
+/// Seconds it takes `workers` elves to do every step, each taking `base`
+/// seconds plus its number.
+// This solution is rather ugly as it is topological sort
+// (which is already implemented above) with additional logic..
+pub fn work_time( graph: &Graph, workers: usize, base: usize ) -> usize {
+    let mut in_degree = in_degrees( graph );
+    let mut zero_degree = BinaryHeap::from_iter(
+        in_degree
+            .iter()
+            .enumerate()
+            .filter( |&(i, &n)| n == 0 && is_step( graph, i ) )
+            .map( |(i, _)| Node( i ) )
+    );
+
+    let mut elves = vec![];
+    let mut time = 0;
+
+    while ! zero_degree.is_empty() || ! elves.is_empty() {
+        while elves.len() < workers && ! zero_degree.is_empty() {
+            let Node( i ) = zero_degree.pop().unwrap();
+            debug!( "{:>5}s: step {} started, {} elves busy", time, letter( i ), elves.len() + 1 );
+            elves.push( (i, 0) );
+        }
+
+        let (idx, (i, done)) = elves
+                                .iter()
+                                .cloned()
+                                .enumerate()
+                                .min_by_key( |&(_, (i, d))| base + 1 + i - d )
+                                .unwrap();
+
+
+        for (j, v) in graph[ i ].iter().enumerate() {
+            if !v { continue; }
+
+            in_degree[ j ] -= 1;
+
+            if in_degree[ j ] == 0 {
+                zero_degree.push( Node( j ) );
+            }
+        }
+
+        elves.remove( idx );
+        let diff = base + 1 + i - done;
+        info!( "{:>5}s: step {} done", time + diff, letter( i ) );
+
+        for (_, w) in &mut elves {
+            *w += diff;
+        }
+
+        time += diff;
+    }
+
+    time
+}
+
+/// Seconds it takes five elves to do every step, each taking 60 seconds plus
+/// its number.
+pub fn solve_part2( graph: &Graph ) -> usize {
+    work_time( graph, WORKERS, BASE )
+}
+
+/// `solve_part1` straight from the input lines.
+pub fn part1<I: Iterator<Item=String>>( input: I ) -> String {
+    solve_part1( &parse( input ) )
+}
+
+/// `solve_part2` straight from the input lines.
+pub fn part2<I: Iterator<Item=String>>( input: I ) -> usize {
+    solve_part2( &parse( input ) )
+}
+
+// Step named by a REPL argument, which has to be part of the graph
+fn step_arg( graph: &Graph, args: &[&str] ) -> Result<usize, String> {
+    let name = arg::<char>( args, 0, "step" )?.to_ascii_uppercase();
+    let step = (name as usize).wrapping_sub( 65 );
+
+    if step >= 26 || ! is_step( graph, step ) {
+        return Err( format!( "there's no step {}", name ) );
+    }
+
+    Ok( step )
+}
+
+fn letters<I: Iterator<Item=usize>>( steps: I ) -> String {
+    let letters = steps.map( |s| letter( s ).to_string() ).collect::<Vec<_>>();
+    if letters.is_empty() { "none".into() } else { letters.join( " " ) }
+}
+
+/// Commands of `repl`, over the graph of steps.
+pub const COMMANDS: [repl::Command<Graph>; 4] = [
+    repl::Command {
+        name: "deps",
+        args: "<step>",
+        help: "steps which have to be finished before <step>",
+        run: |graph, args| {
+            let step = step_arg( graph, args )?;
+            Ok( letters( (0 .. 26).filter( |&s| graph[ s ][ step ] ) ) )
+        }
+    },
+    repl::Command {
+        name: "unlocks",
+        args: "<step>",
+        help: "steps waiting for <step>",
+        run: |graph, args| {
+            let step = step_arg( graph, args )?;
+            Ok( letters( (0 .. 26).filter( |&s| graph[ step ][ s ] ) ) )
+        }
+    },
+    repl::Command {
+        name: "order",
+        args: "",
+        help: "order in which the steps get done",
+        run: |graph, _| Ok( solve_part1( graph ) )
+    },
+    repl::Command {
+        name: "time",
+        args: "[workers] [base]",
+        help: "seconds it takes the elves to do every step",
+        run: |graph, args| {
+            let workers = arg_or( args, 0, "workers", WORKERS )?;
+            if workers == 0 {
+                return Err( "at least one elf has to work".into() );
+            }
+
+            Ok( work_time( graph, workers, arg_or( args, 1, "base", BASE )? ).to_string() )
+        }
+    }
+];
+
+/// Steps are shuffled into a hidden order and edges only ever point forward
+/// in it, so the graph is acyclic. Every step but the first depends on some
+/// earlier one, which makes sure each of them shows up in the input.
+pub fn generate( rng: &mut Rng, size: usize ) -> Vec<String> {
+    let size = size.clamp( 2, 26 );
+    let mut order = (b'A' ..= b'Z').map( |c| c as char ).collect::<Vec<_>>();
+    rng.shuffle( &mut order );
+
+    let mut edges = (1 .. size).map( |to| (rng.below( to ), to) ).collect::<Vec<_>>();
+
+    for _ in 0 .. size * 3 {
+        let (a, b) = (rng.below( size ), rng.below( size ));
+        if a != b {
+            edges.push( (a.min( b ), a.max( b )) );
+        }
+    }
+
+    edges.sort();
+    edges.dedup();
+    rng.shuffle( &mut edges );
+
+    edges
+        .into_iter()
+        .map( |(from, to)| format!( "Step {} must be finished before step {} can begin.", order[ from ], order[ to ] ) )
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc::gen::Rng;
+    use proptest::prelude::*;
+
+    // NOTE: Edges only go "up" in a random ordering, so there are no cycles
+    fn dag() -> impl Strategy<Value=Vec<(usize, usize)>> {
+        (Just( (0 .. 26).collect::<Vec<usize>>() ).prop_shuffle(), prop::collection::vec( (0 .. 26usize, 0 .. 26usize), 0 .. 60 ))
+            .prop_map( |(order, pairs)| {
+                pairs
+                    .into_iter()
+                    .filter( |(a, b)| a < b )
+                    .map( |(a, b)| (order[ a ], order[ b ]) )
+                    .collect()
+            } )
+    }
+
+    fn to_graph( edges: &[(usize, usize)] ) -> Graph {
+        let mut graph = [[false; 26]; 26];
+
+        for &(from, to) in edges {
+            graph[ from ][ to ] = true;
+        }
+
+        graph
+    }
+
+    #[test]
+    fn repl_matches_the_example() {
+        let graph = parse( [ "CA", "CF", "AB", "AD", "BE", "DE", "FE" ].iter().map( |s| {
+            let s = s.as_bytes();
+            format!( "Step {} must be finished before step {} can begin.", s[ 0 ] as char, s[ 1 ] as char )
+        } ) );
+
+        assert_eq!( repl::eval( &graph, &COMMANDS, "deps E" ), Ok( "B D F".into() ) );
+        assert_eq!( repl::eval( &graph, &COMMANDS, "unlocks a" ), Ok( "B D".into() ) );
+        assert_eq!( repl::eval( &graph, &COMMANDS, "deps C" ), Ok( "none".into() ) );
+        assert_eq!( repl::eval( &graph, &COMMANDS, "order" ), Ok( "CABDFE".into() ) );
+        assert_eq!( repl::eval( &graph, &COMMANDS, "time 2 0" ), Ok( "15".into() ) );
+        assert!( repl::eval( &graph, &COMMANDS, "deps Z" ).is_err() );
+        assert!( repl::eval( &graph, &COMMANDS, "time 0" ).is_err() );
+    }
+
+    #[test]
+    fn letters_missing_from_the_input_are_no_steps() {
+        let input = [ "Step C must be finished before step A can begin.", "Step C must be finished before step F can begin." ];
+        assert_eq!( part1( input.iter().map( |l| l.to_string() ) ).to_string(), "CAF" );
+    }
+
+    proptest! {
+        #[test]
+        fn parse_step_reads_both_steps( a in 0 .. 26usize, b in 0 .. 26usize ) {
+            let line = format!( "Step {} must be finished before step {} can begin.", letter( a ), letter( b ) );
+            prop_assert_eq!( parse_step( &line ), (letter( a ), letter( b )) );
+        }
+
+        #[test]
+        fn toposort_respects_every_edge( edges in dag() ) {
+            let sorted = toposort( &to_graph( &edges ) );
+            let mut position = [0; 26];
+
+            for (i, &node) in sorted.iter().enumerate() {
+                position[ node ] = i;
+            }
+
+            let mut all = sorted.clone();
+            all.sort();
+
+            let mut steps = edges.iter().flat_map( |&(a, b)| vec![ a, b ] ).collect::<Vec<_>>();
+            steps.sort();
+            steps.dedup();
+
+            prop_assert_eq!( all, steps );
+            for (from, to) in edges {
+                prop_assert!( position[ from ] < position[ to ] );
+            }
+        }
+
+        #[test]
+        fn toposort_takes_alphabetically_first_step( edges in dag() ) {
+            let graph = to_graph( &edges );
+            let sorted = toposort( &graph );
+
+            for (i, &node) in sorted.iter().enumerate() {
+                let ready = (0 .. 26)
+                                .filter( |&n| is_step( &graph, n ) && ! sorted[ .. i ].contains( &n ) )
+                                .filter( |&n| (0 .. 26).all( |m| ! graph[ m ][ n ] || sorted[ .. i ].contains( &m ) ) )
+                                .min();
+
+                prop_assert_eq!( ready, Some( node ) );
+            }
+        }
+
+        #[test]
+        fn generated_steps_are_all_ordered( seed: u64, size in 2 .. 27usize ) {
+            let input = generate( &mut Rng::new( seed ), size );
+            let order = part1( input.iter().cloned() ).to_string();
+
+            let mut steps = input.iter().map( |l| parse_step( l ) ).flat_map( |(a, b)| vec![ a, b ] ).collect::<Vec<_>>();
+            steps.sort();
+            steps.dedup();
+
+            let mut order = order.chars().collect::<Vec<_>>();
+            order.sort();
+
+            prop_assert_eq!( steps.len(), size );
+            prop_assert_eq!( order, steps );
+        }
+    }
+}
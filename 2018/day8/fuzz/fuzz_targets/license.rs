@@ -0,0 +1,12 @@
+#![no_main]
+
+use day8::Node;
+use libfuzzer_sys::fuzz_target;
+
+// Truncated or malformed licenses have to be rejected with an error, never
+// with a panic.
+fuzz_target!(|data: &[u8]| {
+    if let Ok( input ) = std::str::from_utf8( data ) {
+        let _ = input.parse::<Node>();
+    }
+});
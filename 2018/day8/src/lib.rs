@@ -0,0 +1,335 @@
+//! Parsing and checking of the license tree, e.g. `2 3 0 3 10 11 12 1 1 0 1 99 2 1 1 2`.
+
+use std::mem;
+use std::str::FromStr;
+
+use aoc::gen::Rng;
+use aoc::repl::{self, arg_or};
+
+/// Why the numbers don't make up a license.
+#[derive(Debug)]
+pub struct InvalidLicense(&'static str);
+
+impl std::fmt::Display for InvalidLicense {
+    fn fmt( &self, f: &mut std::fmt::Formatter ) -> std::fmt::Result {
+        write!( f, "invalid license: {}", self.0 )
+    }
+}
+
+impl std::error::Error for InvalidLicense {}
+
+/// Deepest nesting of nodes a license may have.
+pub const MAX_DEPTH: usize = 1000;
+
+/// Node of the license tree with all of its children.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct Node {
+    pub nodes: Vec<Node>,
+    pub metadata: Vec<usize>
+}
+
+impl Node {
+    /// Reads a single node (with all of its children) from `numbers`,
+    /// leaving whatever follows it untouched.
+    pub fn parse_node<I: Iterator<Item=usize>>( numbers: &mut I ) -> Result<Self, InvalidLicense> {
+        Node::parse_below( numbers, 0 )
+    }
+
+    fn parse_below<I: Iterator<Item=usize>>( numbers: &mut I, depth: usize ) -> Result<Self, InvalidLicense> {
+        // NOTE: Nodes are read, checked and dropped recursively, so a tree
+        // nested deeper than this would overflow the stack
+        if depth == MAX_DEPTH {
+            return Err( InvalidLicense( "nodes nested too deep" ) );
+        }
+
+        let mut node = Node::default();
+
+        let nodes_num = numbers.next().ok_or( InvalidLicense( "missing number of children" ) )?;
+        let metadata_num = numbers.next().ok_or( InvalidLicense( "missing number of metadata entries" ) )?;
+
+        for _ in 0 .. nodes_num {
+            let child = Node::parse_below( numbers, depth + 1 )?;
+            node.nodes.push( child );
+        }
+
+        for _ in 0 .. metadata_num {
+            node.metadata.push( numbers.next().ok_or( InvalidLicense( "missing metadata entry" ) )? );
+        }
+
+        Ok( node )
+    }
+
+    /// Sum of the metadata of this node and all the nodes below it.
+    pub fn first_check( &self ) -> usize {
+        let mut sum = self.metadata.iter().sum();
+
+        for node in &self.nodes {
+            sum += node.first_check();
+        }
+
+        sum
+    }
+
+    /// Value of the node: sum of the metadata for a leaf, otherwise sum of
+    /// the values of the children the metadata points at (counting from 1).
+    pub fn second_check( &self ) -> usize {
+        if self.nodes.is_empty() {
+            return self.metadata.iter().sum();
+        }
+
+        self.metadata
+            .iter()
+            .filter( |&idx| *idx > 0 )
+            .filter_map( |&idx| self.nodes.get( idx - 1 ) )
+            .map( |node| node.second_check() )
+            .sum()
+    }
+}
+
+impl FromStr for Node {
+    type Err = InvalidLicense;
+
+    fn from_str( s: &str ) -> Result<Self, Self::Err> {
+        let numbers = s
+                        .split_whitespace()
+                        .map( |n| n.parse::<usize>().map_err( |_| InvalidLicense( "not a number" ) ) )
+                        .collect::<Result<Vec<_>, _>>()?;
+
+        let mut numbers = numbers.into_iter();
+        let node = Node::parse_node( &mut numbers )?;
+
+        if numbers.next().is_some() {
+            return Err( InvalidLicense( "numbers left after the root node" ) );
+        }
+
+        Ok( node )
+    }
+}
+
+/// License tree, on the first line.
+pub fn parse<I: Iterator<Item=String>>( mut input: I ) -> Result<Node, InvalidLicense> {
+    input.next().ok_or( InvalidLicense( "empty input" ) )?.parse::<Node>()
+}
+
+/// Sum of all metadata entries.
+pub fn solve_part1( root: &Node ) -> usize {
+    root.first_check()
+}
+
+/// Value of the root node.
+pub fn solve_part2( root: &Node ) -> usize {
+    root.second_check()
+}
+
+/// `solve_part1` straight from the input lines.
+pub fn part1<I: Iterator<Item=String>>( input: I ) -> Result<usize, InvalidLicense> {
+    Ok( solve_part1( &parse( input )? ) )
+}
+
+/// `solve_part2` straight from the input lines.
+pub fn part2<I: Iterator<Item=String>>( input: I ) -> Result<usize, InvalidLicense> {
+    Ok( solve_part2( &parse( input )? ) )
+}
+
+/// Numbers making up the tree under `node`, the reverse of `Node::parse_node`.
+pub fn serialize( node: &Node ) -> Vec<usize> {
+    let mut numbers = vec![ node.nodes.len(), node.metadata.len() ];
+
+    for child in &node.nodes {
+        numbers.extend( serialize( child ) );
+    }
+
+    numbers.extend( &node.metadata );
+    numbers
+}
+
+// Node at a path of child indices such as `0.2.1`, counting from 0 and
+// starting at the root, which the empty path stands for.
+fn descend<'a>( root: &'a Node, path: &str ) -> Result<&'a Node, String> {
+    path.split( '.' ).filter( |i| ! i.is_empty() ).try_fold( root, |node, i| {
+        let i = i.parse::<usize>().map_err( |_| format!( "invalid child index '{}'", i ) )?;
+        node.nodes.get( i ).ok_or_else( || format!( "no child {} in a node with {} children", i, node.nodes.len() ) )
+    } )
+}
+
+fn describe( node: &Node ) -> String {
+    format!(
+        "{} children, metadata {:?}, sum {}, value {}",
+        node.nodes.len(), node.metadata, node.first_check(), node.second_check()
+    )
+}
+
+fn outline( node: &Node, path: &str, level: usize, depth: usize, lines: &mut Vec<String> ) {
+    let name = if path.is_empty() { "root" } else { path };
+    lines.push( format!( "{}{}: {}", "  ".repeat( level ), name, describe( node ) ) );
+
+    if level < depth {
+        for (i, child) in node.nodes.iter().enumerate() {
+            let child_path = if path.is_empty() { i.to_string() } else { format!( "{}.{}", path, i ) };
+            outline( child, &child_path, level + 1, depth, lines );
+        }
+    }
+}
+
+/// Commands of `repl`, over the root of the license tree.
+pub const COMMANDS: [repl::Command<Node>; 2] = [
+    repl::Command {
+        name: "node",
+        args: "[path]",
+        help: "node at a path of child indices from the root, e.g. 0.2.1",
+        run: |root, args| descend( root, args.first().unwrap_or( &"" ) ).map( describe )
+    },
+    repl::Command {
+        name: "tree",
+        args: "[path] [depth]",
+        help: "nodes under the one at path, two levels deep by default",
+        run: |root, args| {
+            let path = args.first().unwrap_or( &"" ).trim_matches( '.' );
+            let mut lines = vec![];
+
+            outline( descend( root, path )?, path, 0, arg_or( args, 1, "depth", 2 )?, &mut lines );
+            Ok( lines.join( "\n" ) )
+        }
+    }
+];
+
+/// Every node picks a random earlier node as its parent, which keeps the tree
+/// shallow. Metadata of inner nodes sometimes points past the children, like
+/// in the puzzle.
+pub fn generate( rng: &mut Rng, size: usize ) -> Vec<String> {
+    let size = size.max( 1 );
+    let parents = (0 .. size).map( |i| if i > 0 { rng.below( i ) } else { 0 } ).collect::<Vec<_>>();
+    let mut nodes = vec![ Node::default(); size ];
+
+    // NOTE: Children always come after their parent, so going backwards
+    // every node is complete by the time it's moved into its parent
+    for i in ( 0 .. size ).rev() {
+        let children = nodes[ i ].nodes.len() as i64;
+        let max = if children == 0 { 9 } else { children + 1 };
+
+        nodes[ i ].metadata = (0 .. rng.between( 1, 3 ))
+                                .map( |_| rng.between( 1, max ) as usize )
+                                .collect();
+
+        if i > 0 {
+            let node = mem::take( &mut nodes[ i ] );
+            nodes[ parents[ i ] ].nodes.push( node );
+        }
+    }
+
+    vec![ serialize( &nodes[ 0 ] ).iter().map( |n| n.to_string() ).collect::<Vec<_>>().join( " " ) ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc::gen::Rng;
+    use proptest::prelude::*;
+
+    fn node() -> impl Strategy<Value=Node> {
+        let leaf = prop::collection::vec( 0 .. 10usize, 1 .. 4 )
+                        .prop_map( |metadata| Node { nodes: vec![], metadata } );
+
+        leaf.prop_recursive( 4, 32, 4, |inner| {
+            (prop::collection::vec( inner, 0 .. 4 ), prop::collection::vec( 0 .. 10usize, 1 .. 4 ))
+                .prop_map( |(nodes, metadata)| Node { nodes, metadata } )
+        } )
+    }
+
+    fn all_metadata( node: &Node ) -> usize {
+        node.metadata.iter().sum::<usize>() + node.nodes.iter().map( all_metadata ).sum::<usize>()
+    }
+
+    #[test]
+    fn repl_walks_the_example() {
+        let root = "2 3 0 3 10 11 12 1 1 0 1 99 2 1 1 2".parse::<Node>().unwrap();
+
+        assert_eq!( repl::eval( &root, &COMMANDS, "node" ), Ok( "2 children, metadata [1, 1, 2], sum 138, value 66".into() ) );
+        assert_eq!( repl::eval( &root, &COMMANDS, "node 1.0" ), Ok( "0 children, metadata [99], sum 99, value 99".into() ) );
+        assert!( repl::eval( &root, &COMMANDS, "node 1.1" ).is_err() );
+        assert!( repl::eval( &root, &COMMANDS, "node 0.x" ).is_err() );
+        assert_eq!( repl::eval( &root, &COMMANDS, "tree 1" ).unwrap().lines().collect::<Vec<_>>(), vec![
+            "1: 1 children, metadata [2], sum 101, value 0",
+            "  1.0: 0 children, metadata [99], sum 99, value 99"
+        ] );
+    }
+
+    #[test]
+    fn parts_reject_missing_or_broken_license() {
+        assert!( parse( std::iter::empty() ).is_err() );
+        assert!( part1( std::iter::once( "1 1 0".to_string() ) ).is_err() );
+        assert!( part2( std::iter::once( "0 1 x".to_string() ) ).is_err() );
+    }
+
+    #[test]
+    fn very_deep_license_is_an_error() {
+        let line = "1 0 ".repeat( 100_000 ) + "0 0";
+        assert!( line.parse::<Node>().is_err() );
+
+        let nested = "1 0 ".repeat( MAX_DEPTH - 1 ) + "0 1 7";
+        assert_eq!( nested.parse::<Node>().unwrap().first_check(), 7 );
+    }
+
+    proptest! {
+        #[test]
+        fn node_round_trips( tree in node() ) {
+            let numbers = serialize( &tree );
+            let mut iter = numbers.iter().cloned();
+
+            prop_assert_eq!( Node::parse_node( &mut iter ).unwrap(), tree );
+            prop_assert!( iter.next().is_none() );
+        }
+
+        #[test]
+        fn incomplete_license_is_an_error( tree in node(), cut in any::<prop::sample::Index>(), extra in 0 .. 10usize ) {
+            let numbers = serialize( &tree );
+            let line = |numbers: &[usize]| numbers.iter().map( |n| n.to_string() ).collect::<Vec<_>>().join( " " );
+
+            let cut = cut.index( numbers.len() );
+            prop_assert!( line( &numbers[ .. cut ] ).parse::<Node>().is_err() );
+
+            let mut longer = numbers.clone();
+            longer.push( extra );
+            prop_assert!( line( &longer ).parse::<Node>().is_err() );
+        }
+
+        #[test]
+        fn first_check_sums_all_metadata( tree in node() ) {
+            prop_assert_eq!( tree.first_check(), all_metadata( &tree ) );
+        }
+
+        #[test]
+        fn second_check_follows_references( tree in node() ) {
+            let expected = if tree.nodes.is_empty() {
+                tree.metadata.iter().sum()
+            } else {
+                tree.metadata
+                    .iter()
+                    .map( |&i| if (1 ..= tree.nodes.len()).contains( &i ) { tree.nodes[ i - 1 ].second_check() } else { 0 } )
+                    .sum()
+            };
+
+            prop_assert_eq!( tree.second_check(), expected );
+        }
+
+        #[test]
+        fn parts_read_serialized_license( tree in node() ) {
+            let line = serialize( &tree ).iter().map( |n| n.to_string() ).collect::<Vec<_>>().join( " " );
+
+            prop_assert_eq!( part1( std::iter::once( line.clone() ) ).unwrap(), tree.first_check() );
+            prop_assert_eq!( part2( std::iter::once( line ) ).unwrap(), tree.second_check() );
+        }
+
+        #[test]
+        fn generated_license_has_every_node( seed: u64, size in 1 .. 500usize ) {
+            let input = generate( &mut Rng::new( seed ), size );
+            let tree = input[ 0 ].parse::<Node>().unwrap();
+
+            fn count( node: &Node ) -> usize {
+                1 + node.nodes.iter().map( count ).sum::<usize>()
+            }
+
+            prop_assert_eq!( count( &tree ), size );
+        }
+    }
+}
@@ -0,0 +1,126 @@
+//! Marble Mania: games such as `10 players; last marble is worth 1618 points`.
+
+use std::collections::VecDeque;
+use std::iter::{self, FromIterator};
+
+use aoc::gen::Rng;
+use lazy_static::lazy_static;
+use regex::Regex;
+
+/// Number of players and value of the last marble.
+pub fn parse_input( s: &str ) -> (usize, usize) {
+    lazy_static! {
+        static ref RE: Regex = Regex::new( r"(\d+) players; last marble is worth (\d+) points" ).unwrap();
+    }
+
+    let cap = RE.captures( s ).unwrap();
+    (cap[ 1 ].parse().unwrap(), cap[ 2 ].parse().unwrap())
+}
+
+/// Winning score once every marble up to `marbles` has been played.
+pub fn play_game( players: usize, marbles: usize ) -> usize {
+    let mut game = VecDeque::from_iter( iter::once( 0 ) );
+    let mut scores = vec![0; players];
+
+    for i in 1 ..= marbles {
+        if (i % 23) == 0 {
+            game.rotate_right( 7 );
+            let num = game.pop_back().unwrap();
+            scores[ i % players ] += num + i;
+            game.rotate_left( 1 );
+        } else {
+            game.rotate_left( 1 );
+            game.push_back( i );
+        }
+    }
+
+    scores.into_iter().max().unwrap()
+}
+
+/// Number of players and value of the last marble, on the first line.
+pub fn parse<I: Iterator<Item=String>>( mut input: I ) -> (usize, usize) {
+    parse_input( &input.next().unwrap() )
+}
+
+/// Winning score of the game.
+pub fn solve_part1( &(players, marbles): &(usize, usize) ) -> usize {
+    play_game( players, marbles )
+}
+
+/// How many times bigger the last marble is in part two.
+pub const MULTIPLIER: usize = 100;
+
+/// Winning score with a last marble 100 times bigger.
+pub fn solve_part2( &(players, marbles): &(usize, usize) ) -> usize {
+    play_game( players, marbles * MULTIPLIER )
+}
+
+/// `solve_part1` straight from the input lines.
+pub fn part1<I: Iterator<Item=String>>( input: I ) -> usize {
+    solve_part1( &parse( input ) )
+}
+
+/// `solve_part2` straight from the input lines.
+pub fn part2<I: Iterator<Item=String>>( input: I ) -> usize {
+    solve_part2( &parse( input ) )
+}
+
+/// The size is the value of the last marble.
+pub fn generate( rng: &mut Rng, size: usize ) -> Vec<String> {
+    vec![ format!( "{} players; last marble is worth {} points", rng.between( 2, 500 ), size.max( 1 ) ) ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc::gen::Rng;
+    use proptest::prelude::*;
+
+    // NOTE: Straightforward simulation on a plain vector, as in the puzzle text
+    fn naive_game( players: usize, marbles: usize ) -> usize {
+        let mut circle = vec![ 0 ];
+        let mut current = 0;
+        let mut scores = vec![ 0; players ];
+
+        for i in 1 ..= marbles {
+            if i % 23 == 0 {
+                current = (current + circle.len() - 7) % circle.len();
+                scores[ i % players ] += i + circle.remove( current );
+            } else {
+                current = (current + 1) % circle.len() + 1;
+                circle.insert( current, i );
+            }
+        }
+
+        scores.into_iter().max().unwrap()
+    }
+
+    #[test]
+    fn examples() {
+        assert_eq!( play_game( 9, 25 ), 32 );
+        assert_eq!( play_game( 10, 1618 ), 8317 );
+        assert_eq!( play_game( 13, 7999 ), 146373 );
+        assert_eq!( play_game( 30, 5807 ), 37305 );
+    }
+
+    proptest! {
+        #[test]
+        fn play_game_matches_naive_game( players in 1 .. 50usize, marbles in 0 .. 2000usize ) {
+            prop_assert_eq!( play_game( players, marbles ), naive_game( players, marbles ) );
+        }
+
+        #[test]
+        fn parse_input_reads_numbers( players in any::<u32>(), points in any::<u32>() ) {
+            let line = format!( "{} players; last marble is worth {} points", players, points );
+            prop_assert_eq!( parse_input( &line ), (players as usize, points as usize) );
+        }
+
+        #[test]
+        fn generated_game_parses( seed: u64, size in 1 .. 100_000usize ) {
+            let (players, marbles) = parse_input( &generate( &mut Rng::new( seed ), size )[ 0 ] );
+
+            prop_assert!( (2 ..= 500).contains( &players ) );
+            prop_assert_eq!( marbles, size );
+        }
+    }
+}
@@ -0,0 +1,26 @@
+//! Puzzle name: what a line of the input looks like.
+
+/// Input, one line at a time.
+pub fn parse<I: Iterator<Item=String>>( input: I ) -> Vec<String> {
+    input.collect()
+}
+
+/// Answer to the first part.
+pub fn solve_part1( _input: &[String] ) -> usize {
+    42
+}
+
+/// Answer to the second part.
+pub fn solve_part2( _input: &[String] ) -> usize {
+    42
+}
+
+/// `solve_part1` straight from the input lines.
+pub fn part1<I: Iterator<Item=String>>( input: I ) -> usize {
+    solve_part1( &parse( input ) )
+}
+
+/// `solve_part2` straight from the input lines.
+pub fn part2<I: Iterator<Item=String>>( input: I ) -> usize {
+    solve_part2( &parse( input ) )
+}
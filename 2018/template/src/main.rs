@@ -1,36 +1,41 @@
 use std::env;
-use std::fmt::Display;
 use std::fs;
 use std::io::{BufRead, BufReader};
 
-fn part1<I: Iterator<Item=String>>( input: I ) -> impl Display {
-    42
-}
+use aoc::config::Config;
+use aoc::mem;
+use template::{parse, part1, part2, solve_part1, solve_part2};
 
-fn part2<I: Iterator<Item=String>>( input: I ) -> impl Display {
-    42
-}
+#[global_allocator]
+static ALLOCATOR: mem::Counting = mem::Counting;
 
 fn usage() -> &'static str {
-    "usage: cargo run --release -- <part1|part2>"
+    "usage: cargo run --release -- <part1|part2> [input] | --profile-mem [input]"
 }
 
-fn main() -> Result<(), Box<std::error::Error>> {
-    let part = env::args().nth( 1 ).unwrap_or_else( || "part1".into() );
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let config = Config::load( env!( "CARGO_MANIFEST_DIR" ) )?;
+    config.watch();
+
+    let part = env::args().nth( 1 ).unwrap_or_else( || config.part() );
+    if part == "--profile-mem" {
+        let input_file = env::args().nth( 2 ).unwrap_or_else( || config.input() );
+        return mem::profile( &input_file, parse, |input| solve_part1( input ), |input| solve_part2( input ) );
+    }
+
     let first = match part.as_ref() {
         "part1"     => true,
         "part2"     => false,
         _           => return Err( usage().into() )
     };
 
-    let input_file = env::args().nth( 2 ).unwrap_or_else( || "res/input".into() );
+    let input_file = env::args().nth( 2 ).unwrap_or_else( || config.input() );
 
     let reader = BufReader::new( fs::File::open( input_file )? );
     let mut lines = reader.lines().map( |l| l.expect( "invalid input" ) );
 
-    if first { println!( "{}", part1( &mut lines ) ) }
-    else     { println!( "{}", part2( &mut lines ) ) };
-
+    if first { config.print( "part1", part1( &mut lines ) ) }
+    else     { config.print( "part2", part2( &mut lines ) ) };
 
     Ok( () )
 }